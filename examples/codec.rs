@@ -0,0 +1,57 @@
+use serde_builder::{case::Case, codec::StructCodec};
+use serde_json::json;
+
+#[derive(Debug, PartialEq)]
+struct User {
+    user_name: String,
+    display_name: String,
+    age: u32,
+}
+
+fn main() {
+    // builders are consumed when used, so the codec is created once per direction
+    let user_codec = || {
+        StructCodec::new()
+            .field("user_name", |u: &User| &u.user_name)
+            .field("display_name", |u: &User| &u.display_name)
+            .field("age", |u: &User| &u.age)
+            .rename_all(Case::Camel)
+            .final_builder(|user_name, display_name, age| User {
+                user_name,
+                display_name,
+                age,
+            })
+    };
+    let user = User {
+        user_name: "foo".into(),
+        display_name: "Foo".into(),
+        age: 42,
+    };
+    let serialized = user_codec()
+        .serialize(&user, serde_json::value::Serializer)
+        .expect("Serialization failed");
+    assert_eq!(
+        serialized,
+        json!({"userName": "foo", "displayName": "Foo", "age": 42})
+    );
+    let deserialized = user_codec()
+        .deserialize(&serialized)
+        .expect("Deserialization failed");
+    assert_eq!(deserialized, user);
+
+    // renamed structs stay structs, so formats that write them as plain sequences round trip
+    let mut bytes = Vec::new();
+    user_codec()
+        .serialize(
+            &user,
+            &mut bincode::Serializer::new(&mut bytes, bincode::DefaultOptions::new()),
+        )
+        .expect("Serialization failed");
+    let from_bincode = user_codec()
+        .deserialize(&mut bincode::Deserializer::from_slice(
+            &bytes,
+            bincode::DefaultOptions::new(),
+        ))
+        .expect("Deserialization failed");
+    assert_eq!(from_bincode, user);
+}
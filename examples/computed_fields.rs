@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use serde_builder::de::StructDeserializer;
+use serde_json::json;
+
+#[derive(Debug, PartialEq)]
+struct Rect {
+    width: u32,
+    height: u32,
+    area: u32,
+    revision: u32,
+}
+
+fn main() {
+    // computed and skipped fields have no key, their values never come from the input
+    let rect_deserializer = || {
+        StructDeserializer::new()
+            .field("width")
+            .field("height")
+            .computed_field(|&(width, height): &(u32, u32)| width * height)
+            .skip_field(|| 1)
+            .final_builder(|width, height, area, revision| Rect {
+                width,
+                height,
+                area,
+                revision,
+            })
+    };
+
+    let input = json!({"width": 3, "height": 4, "area": 100, "revision": 7});
+    let rect = rect_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(
+        rect,
+        Rect {
+            width: 3,
+            height: 4,
+            area: 12,
+            revision: 1,
+        }
+    );
+
+    let input = json!({"width": 3, "area": 12});
+    let missing = rect_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert!(missing.to_string().starts_with("missing field"));
+
+    let input = json!({"width": 3, "height": 4, "area": 12});
+    let unknown = rect_deserializer()
+        .deny_unknown_fields()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        unknown.to_string(),
+        "unknown field `area`, expected `width` or `height`"
+    );
+
+    // a sequence holds only the fields read from the input
+    let input = json!([5, 2]);
+    let rect = rect_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(rect.area, 10);
+
+    let input = json!([5, 2, 10]);
+    let too_long = rect_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert!(too_long.to_string().starts_with("invalid length 3"));
+
+    // fields that aren't read from the input can't be given an id or a duplicate policy
+    let mut msgpack = Vec::new();
+    rmp_serde::encode::write(
+        &mut msgpack,
+        &[(1u8, 10u32), (2, 99), (3, 7)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    )
+    .expect("Serialization failed");
+    let with_id = StructDeserializer::new()
+        .field_id(1)
+        .skip_field(|| 0)
+        .id(2)
+        .field_id(3)
+        .final_builder(|a: u32, b: u32, c: u32| (a, b, c))
+        .deserialize(&mut rmp_serde::Deserializer::new(&msgpack[..]))
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        with_id.to_string(),
+        "`id` was called on a skipped or computed field, which isn't read from the input"
+    );
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Deserializer};
+use serde_builder::{
+    de::{DeserializeWith, StructDeserializer},
+    description::Presence,
+};
+use serde_json::json;
+
+#[derive(Debug, PartialEq)]
+struct Payment {
+    method: String,
+    card: Option<String>,
+    iban: Option<String>,
+}
+
+/// Reads an IBAN with the spaces removed
+struct Compact;
+
+impl DeserializeWith<String> for Compact {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        let iban = String::deserialize(deserializer)?;
+        Ok(iban.split_whitespace().collect())
+    }
+}
+
+fn main() {
+    // The predicates receive the arguments of the fields added before the optional field
+    let payment_deserializer = || {
+        StructDeserializer::new()
+            .field("method")
+            .optional_field("card")
+            .required_if("`method` is \"card\"", |(method,): &(String,)| {
+                method == "card"
+            })
+            .optional_field_with::<Compact, _, _>("iban")
+            .required_unless(
+                "`method` isn't \"transfer\"",
+                |(method, _): &(String, Option<String>)| method != "transfer",
+            )
+            .final_builder(|method, card, iban| Payment { method, card, iban })
+    };
+
+    // The condition is also given to `describe()`
+    let description = payment_deserializer().describe();
+    assert_eq!(
+        description.fields[1].presence,
+        Presence::Conditional("required if `method` is \"card\"".to_owned())
+    );
+
+    let input = json!({"method": "card", "card": "4242"});
+    let payment = payment_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(payment.card.as_deref(), Some("4242"));
+    assert_eq!(payment.iban, None);
+
+    let input = json!({"method": "transfer", "iban": "DE89 3704 0044"});
+    let payment = payment_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(payment.card, None);
+    assert_eq!(payment.iban.as_deref(), Some("DE8937040044"));
+
+    let input = json!({"method": "cash"});
+    let payment = payment_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(
+        payment,
+        Payment {
+            method: "cash".to_owned(),
+            card: None,
+            iban: None
+        }
+    );
+
+    let input = json!({"method": "card"});
+    let missing_card = payment_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        missing_card.to_string(),
+        "missing field `card`, required if `method` is \"card\""
+    );
+
+    let input = json!({"method": "transfer", "card": "4242"});
+    let missing_iban = payment_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        missing_iban.to_string(),
+        "missing field `iban`, required unless `method` isn't \"transfer\""
+    );
+}
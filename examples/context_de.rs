@@ -0,0 +1,92 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde_builder::de::{StructDeserializer, WithContext};
+
+/// Shared state used while deserializing: known users and interned tags
+#[derive(Default)]
+struct Registry {
+    users: HashMap<u64, String>,
+    tags: BTreeSet<String>,
+    posts_read: usize,
+}
+
+#[derive(Debug, PartialEq)]
+struct Comment {
+    author: String,
+    text: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct Post {
+    author: String,
+    tags: Vec<String>,
+    pinned: Comment,
+}
+
+fn resolve_user(registry: &mut Registry, id: u64) -> String {
+    registry
+        .users
+        .get(&id)
+        .cloned()
+        .unwrap_or_else(|| format!("unknown user {id}"))
+}
+
+fn main() {
+    let mut registry = Registry::default();
+    registry.users.insert(1, "alice".into());
+    registry.users.insert(2, "bob".into());
+
+    let comment_deserializer = StructDeserializer::new()
+        .context::<Registry>()
+        .context_field("author", resolve_user)
+        .field("text")
+        .final_builder(|author, text| Comment { author, text });
+
+    let post = StructDeserializer::new()
+        .context::<Registry>()
+        .context_field("author", resolve_user)
+        .context_field("tags", |registry: &mut Registry, tags: Vec<String>| {
+            registry.tags.extend(tags.iter().cloned());
+            tags
+        })
+        .nested_field("pinned", comment_deserializer)
+        .final_builder(WithContext(
+            |registry: &mut Registry, author, tags, pinned| {
+                registry.posts_read += 1;
+                Post {
+                    author,
+                    tags,
+                    pinned,
+                }
+            },
+        ))
+        .validator(WithContext(|registry: &mut Registry, post: &Post| {
+            if registry.users.values().any(|user| *user == post.author) {
+                Ok(())
+            } else {
+                Err(format!("{} is not a registered user", post.author))
+            }
+        }))
+        .deserialize_with_context(
+            &mut registry,
+            &mut serde_json::Deserializer::from_str(
+                r#"{"author": 1, "tags": ["rust", "serde"], "pinned": {"author": 2, "text": "nice"}}"#,
+            ),
+        )
+        .expect("Deserialization failed");
+
+    assert_eq!(
+        post,
+        Post {
+            author: "alice".into(),
+            tags: vec!["rust".into(), "serde".into()],
+            pinned: Comment {
+                author: "bob".into(),
+                text: "nice".into(),
+            },
+        }
+    );
+    assert_eq!(registry.posts_read, 1);
+    assert!(registry.tags.contains("serde"));
+    println!("{:?}", post);
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use serde_builder::{impl_serde, Builders};
+use serde_json::json;
+
+mod seconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
+}
+
+#[derive(Debug, PartialEq, Builders)]
+#[serde_builder(validator = "validate_server")]
+struct Server {
+    #[serde_builder(rename = "hostname")]
+    host: String,
+    #[serde_builder(default = "default_port", validator = "validate_port")]
+    port: u16,
+    #[serde_builder(default)]
+    tags: Vec<String>,
+    #[serde_builder(with = "seconds")]
+    timeout: Duration,
+}
+
+impl_serde!(Server);
+
+fn default_port() -> u16 {
+    8080
+}
+
+fn validate_port(port: &u16) -> Result<(), String> {
+    match *port {
+        0 => Err("port must not be zero".into()),
+        _ => Ok(()),
+    }
+}
+
+fn validate_server(server: &Server) -> Result<(), String> {
+    match server.timeout.is_zero() {
+        true => Err("timeout must not be zero".into()),
+        false => Ok(()),
+    }
+}
+
+fn main() {
+    let input = json!({"hostname": "localhost", "timeout": 30});
+    let server = Server::struct_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(
+        server,
+        Server {
+            host: "localhost".into(),
+            port: 8080,
+            tags: Vec::new(),
+            timeout: Duration::from_secs(30),
+        }
+    );
+
+    let output = Server::struct_serializer()
+        .serialize(&server, serde_json::value::Serializer)
+        .expect("Serialization failed");
+    assert_eq!(
+        output,
+        json!({"hostname": "localhost", "port": 8080, "tags": [], "timeout": 30})
+    );
+
+    let servers: Vec<Server> =
+        serde_json::from_value(json!([input, {"hostname": "remote", "port": 80, "timeout": 5}]))
+            .expect("Deserialization failed");
+    assert_eq!(servers[0], server);
+    assert_eq!(servers[1].port, 80);
+
+    let msgpack = rmp_serde::to_vec(&servers).expect("Serialization failed");
+    let from_msgpack: Vec<Server> = rmp_serde::from_slice(&msgpack).expect("Parsing failed");
+    assert_eq!(from_msgpack, servers);
+
+    // bincode writes structs as their fields in order, defaulted ones included
+    let bytes = bincode::serialize(&servers).expect("Serialization failed");
+    let from_bincode: Vec<Server> = bincode::deserialize(&bytes).expect("Parsing failed");
+    assert_eq!(from_bincode, servers);
+
+    let invalid = json!({"hostname": "localhost", "timeout": 0});
+    let result = Server::struct_deserializer().deserialize(&invalid);
+    println!("{:?}", result);
+    assert!(result.is_err());
+
+    // field validators run before the struct's one
+    let invalid = json!({"hostname": "localhost", "port": 0, "timeout": 0});
+    let error = Server::struct_deserializer()
+        .deserialize(&invalid)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(error.to_string(), "port: port must not be zero");
+}
@@ -0,0 +1,90 @@
+use serde_builder::{
+    case::Case, de::StructDeserializer, description::Presence, ser::StructSerializer,
+};
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Shape {
+    name: String,
+    origin: Point,
+    tags: Vec<String>,
+    version: u32,
+}
+
+fn main() {
+    let point_deserializer = StructDeserializer::new()
+        .field("x")
+        .field("y")
+        .final_builder(|x, y| Point { x, y });
+    let shape_deserializer = StructDeserializer::new()
+        .field("shape_name")
+        .id(1)
+        .nested_field("origin", point_deserializer)
+        .collect_field("tag")
+        .skip_field(|| 2)
+        .rename_all(Case::Camel)
+        .final_builder(|name, origin, tags, version| Shape {
+            name,
+            origin,
+            tags,
+            version,
+        });
+
+    let description = shape_deserializer.describe();
+    println!("{description:#?}");
+    assert_eq!(description.name, "Shape");
+    assert!(description.type_name.ends_with("Shape"));
+    let names: Vec<_> = description
+        .fields
+        .iter()
+        .map(|field| field.name.as_deref())
+        .collect();
+    assert_eq!(
+        names,
+        [Some("shapeName"), Some("origin"), Some("tag"), None]
+    );
+    assert_eq!(description.fields[0].id, Some(1));
+    assert!(description.fields[0].type_name.ends_with("String"));
+    assert!(description.fields[2].repeatable);
+    assert_eq!(description.fields[3].presence, Presence::Skipped);
+    let origin = description.fields[1].nested.as_ref().unwrap();
+    assert_eq!(origin.name, "Point");
+    assert_eq!(origin.fields.len(), 2);
+    assert_eq!(origin.fields[0].type_name, "i32");
+
+    let shape_serializer = StructSerializer::new()
+        .field("shape_name", |s: &Shape| &s.name)
+        .field("tags", |s: &Shape| &s.tags)
+        .field("version", |s: &Shape| &s.version)
+        .rename_all(Case::Camel)
+        .name("Figure");
+    let description = shape_serializer.describe();
+    println!("{description:#?}");
+    assert_eq!(description.name, "Figure");
+    assert_eq!(description.fields[0].name.as_deref(), Some("shapeName"));
+    assert_eq!(description.fields[2].type_name, "u32");
+
+    let shape = Shape {
+        name: "square".into(),
+        origin: Point { x: 0, y: 0 },
+        tags: vec![],
+        version: 2,
+    };
+    shape_serializer
+        .serialize(&shape, &mut serde_json::Serializer::new(std::io::sink()))
+        .expect("Serialization failed");
+    assert_eq!(shape.origin.x + shape.origin.y, 0);
+
+    // Types that aren't a plain path keep their full name
+    let pair_deserializer = StructDeserializer::new()
+        .field("first")
+        .field("second")
+        .final_builder(|first: String, second: u32| (first, second));
+    assert_eq!(
+        pair_deserializer.describe().name,
+        "(alloc::string::String, u32)"
+    );
+}
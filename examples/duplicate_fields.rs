@@ -0,0 +1,82 @@
+use serde_builder::de::{DuplicateFields, StructDeserializer};
+
+#[derive(Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq)]
+struct Deployment {
+    name: String,
+    server: Server,
+}
+
+fn main() {
+    let server_deserializer = || {
+        StructDeserializer::new()
+            .field("host")
+            .field("port")
+            .final_builder(|host, port| Server { host, port })
+    };
+    let input = r#"{"host": "a.example.com", "port": 80, "host": "b.example.com"}"#;
+
+    // Repeated keys are rejected by default
+    let mut deser = serde_json::Deserializer::from_str(input);
+    let duplicate = server_deserializer()
+        .deserialize(&mut deser)
+        .unwrap_err()
+        .into_de_error();
+    assert!(duplicate.to_string().starts_with("duplicate field"));
+
+    let mut deser = serde_json::Deserializer::from_str(input);
+    let first = server_deserializer()
+        .duplicate_fields(DuplicateFields::First)
+        .deserialize(&mut deser)
+        .expect("Deserialization failed");
+    assert_eq!(first.host, "a.example.com");
+
+    let mut deser = serde_json::Deserializer::from_str(input);
+    let last = server_deserializer()
+        .duplicate_fields(DuplicateFields::Last)
+        .deserialize(&mut deser)
+        .expect("Deserialization failed");
+    assert_eq!(last.host, "b.example.com");
+
+    // A field's own policy takes precedence over the struct's
+    let mut deser = serde_json::Deserializer::from_str(input);
+    let own_policy = StructDeserializer::new()
+        .field("host")
+        .on_duplicate(DuplicateFields::Last)
+        .field("port")
+        .final_builder(|host, port| Server { host, port })
+        .deserialize(&mut deser)
+        .expect("Deserialization failed");
+    assert_eq!(own_policy.host, "b.example.com");
+
+    let input = r#"{"server": {"host": "a.example.com", "port": 80}, "name": "web", "server": {"host": "b.example.com", "port": 8080}}"#;
+    let mut deser = serde_json::Deserializer::from_str(input);
+    let deployment = StructDeserializer::new()
+        .field("name")
+        .nested_field("server", server_deserializer())
+        .duplicate_fields(DuplicateFields::First)
+        .final_builder(|name, server| Deployment { name, server })
+        .deserialize(&mut deser)
+        .expect("Deserialization failed");
+    assert_eq!(deployment.server.port, 80);
+
+    // A nested deserializer reads a single occurrence, so nested fields can't keep the last one
+    let mut deser = serde_json::Deserializer::from_str(input);
+    let nested_last = StructDeserializer::new()
+        .field("name")
+        .nested_field("server", server_deserializer())
+        .duplicate_fields(DuplicateFields::Last)
+        .final_builder(|name, server| Deployment { name, server })
+        .deserialize(&mut deser)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        nested_last.to_string(),
+        "nested field `server` can't use `DuplicateFields::Last`, its deserializer reads only one occurrence"
+    );
+}
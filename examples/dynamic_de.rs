@@ -0,0 +1,90 @@
+use serde_builder::de::{DynDecoder, DynFields, DynStructDeserializer};
+
+#[derive(Debug, PartialEq)]
+enum Setting {
+    Flag(bool),
+    Number(i64),
+    Text(String),
+}
+
+#[derive(Debug, PartialEq)]
+struct PluginConfig {
+    settings: Vec<(String, Setting)>,
+}
+
+fn main() {
+    // Schema as it would be read from a plugin manifest
+    let schema = [("enabled", "bool"), ("retries", "int"), ("label", "text")];
+
+    let mut deserializer = DynStructDeserializer::new();
+    for (name, kind) in schema {
+        let decoder = match kind {
+            "bool" => DynDecoder::map(Setting::Flag),
+            "int" => DynDecoder::map(Setting::Number),
+            _ => DynDecoder::map(Setting::Text),
+        };
+        deserializer = match name {
+            "label" => deserializer.optional_field(name, decoder),
+            "retries" => deserializer.default_field(name, decoder, || Setting::Number(3)),
+            _ => deserializer.field(name, decoder),
+        };
+    }
+    let config = deserializer
+        .final_builder(move |mut fields: DynFields| PluginConfig {
+            settings: schema
+                .iter()
+                .filter_map(|(name, _)| Some((name.to_string(), fields.take(name)?)))
+                .collect(),
+        })
+        .validator(|config: &PluginConfig| {
+            if config.settings.is_empty() {
+                Err("no settings".into())
+            } else {
+                Ok(())
+            }
+        })
+        .deserialize(&mut serde_json::Deserializer::from_str(
+            r#"{"enabled": true}"#,
+        ))
+        .expect("Deserialization failed");
+    assert_eq!(
+        config.settings,
+        vec![
+            ("enabled".into(), Setting::Flag(true)),
+            ("retries".into(), Setting::Number(3)),
+        ]
+    );
+
+    let mut empty_deser = serde_json::Deserializer::from_str("{}");
+    let missing = DynStructDeserializer::new()
+        .field("enabled", DynDecoder::of::<bool>())
+        .final_builder(|fields: DynFields| fields.get::<bool>("enabled").copied())
+        .deserialize(&mut empty_deser);
+    println!("{:?}", missing);
+    assert!(missing.is_err());
+
+    let mut duplicate_deser =
+        serde_json::Deserializer::from_str(r#"{"enabled": true, "enabled": false}"#);
+    let duplicate = DynStructDeserializer::new()
+        .name("PluginConfig")
+        .field("enabled", DynDecoder::of::<bool>())
+        .final_builder(|fields: DynFields| fields.get::<bool>("enabled").copied())
+        .deserialize(&mut duplicate_deser)
+        .unwrap_err()
+        .into_de_error();
+    assert!(duplicate.to_string().starts_with("duplicate field"));
+
+    // Options of the last field set before any field was added are reported, not panicked on
+    let mut misconfigured_deser = serde_json::Deserializer::from_str(r#"{"enabled": true}"#);
+    let misconfigured = DynStructDeserializer::new()
+        .id(1)
+        .field("enabled", DynDecoder::of::<bool>())
+        .final_builder(|fields: DynFields| fields.get::<bool>("enabled").copied())
+        .deserialize(&mut misconfigured_deser)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        misconfigured.to_string(),
+        "`id` was called before any field was added"
+    );
+}
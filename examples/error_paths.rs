@@ -0,0 +1,94 @@
+use serde_builder::{
+    de::{Path, Segment, StructDeserializer},
+    impl_serde,
+    ser::StructSerializer,
+};
+
+#[derive(Debug)]
+struct Tls {
+    port: u16,
+}
+
+#[derive(Debug)]
+struct Server {
+    host: String,
+    tls: Tls,
+    tags: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Port(u16);
+
+impl_serde! {
+    Port {
+        deserializer: StructDeserializer::new()
+            .field("port")
+            .final_builder(Port),
+        serializer: StructSerializer::new().field("port", |p: &Port| &p.0),
+    }
+}
+
+fn read_server(input: &str) -> Result<Server, Path> {
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    let tls_deserializer = StructDeserializer::new()
+        .field("port")
+        .final_builder(|port| Tls { port });
+    let result = StructDeserializer::new()
+        .field("host")
+        .nested_field("tls", tls_deserializer)
+        .collect_field("tag")
+        .final_builder(|host, tls, tags| Server { host, tls, tags })
+        .deserialize(&mut deserializer);
+    println!("{result:?}");
+    result.map_err(|error| error.path().clone())
+}
+
+fn hosts_path(input: &str) -> Path {
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    let result = StructDeserializer::new()
+        .field("hosts")
+        .final_builder(|hosts: Vec<String>| hosts)
+        .deserialize(&mut deserializer);
+    println!("{result:?}");
+    result.unwrap_err().path().clone()
+}
+
+fn main() {
+    let server = read_server(r#"{"host": "a", "tls": {"port": 443}, "tag": "x"}"#).unwrap();
+    assert_eq!((server.host.as_str(), server.tls.port), ("a", 443));
+    assert_eq!(server.tags, ["x"]);
+
+    // The builders record the fields leading to the failure, nested builders included
+    let nested = read_server(r#"{"host": "a", "tls": {"port": "443"}}"#).unwrap_err();
+    assert_eq!(nested.to_string(), "tls.port");
+
+    // Occurrences of a collected field are numbered
+    let collected =
+        read_server(r#"{"host": "a", "tls": {"port": 443}, "tag": "x", "tag": 1}"#).unwrap_err();
+    assert_eq!(
+        collected.segments(),
+        [Segment::Field("tag".into()), Segment::Index(1)]
+    );
+
+    // Plain serde values are only looked into with the `value-paths` feature
+    let inner = hosts_path(r#"{"hosts": ["a", 1]}"#);
+    #[cfg(feature = "value-paths")]
+    assert_eq!(inner.to_string(), "hosts[1]");
+    #[cfg(not(feature = "value-paths"))]
+    assert_eq!(inner.to_string(), "hosts");
+
+    // Builders inside plain values keep their part of the path, what leads to them is only
+    // known with `value-paths`
+    let mut deserializer =
+        serde_json::Deserializer::from_str(r#"{"ports": [{"port": 1}, {"port": "2"}]}"#);
+    let result = StructDeserializer::new()
+        .field("ports")
+        .final_builder(|ports: Vec<Port>| ports)
+        .deserialize(&mut deserializer);
+    println!("{result:?}");
+    let through_vec = result.unwrap_err().path().clone();
+    #[cfg(feature = "value-paths")]
+    assert_eq!(through_vec.to_string(), "ports[1].port");
+    #[cfg(not(feature = "value-paths"))]
+    assert_eq!(through_vec.to_string(), "ports.?.port");
+}
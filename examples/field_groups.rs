@@ -0,0 +1,99 @@
+use serde_builder::de::{DynDecoder, DynFields, DynStructDeserializer, StructDeserializer};
+use serde_json::json;
+
+#[derive(Debug, PartialEq)]
+struct Contact {
+    email: Option<String>,
+    phone: Option<String>,
+    nickname: Option<String>,
+    alias: Option<String>,
+}
+
+fn main() {
+    let contact_deserializer = || {
+        StructDeserializer::new()
+            .optional_field("email")
+            .optional_field("phone")
+            .optional_field("nickname")
+            .optional_field("alias")
+            .exactly_one_of(&["email", "phone"])
+            .at_most_one_of(&["nickname", "alias"])
+            .final_builder(|email, phone, nickname, alias| Contact {
+                email,
+                phone,
+                nickname,
+                alias,
+            })
+    };
+
+    let input = json!({"email": "ada@example.com", "nickname": "ada"});
+    let contact = contact_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(contact.email.as_deref(), Some("ada@example.com"));
+    assert_eq!(contact.nickname.as_deref(), Some("ada"));
+
+    let input = json!({"phone": "555-0100"});
+    let contact = contact_deserializer()
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(contact.phone.as_deref(), Some("555-0100"));
+
+    let input = json!({"nickname": "ada"});
+    let missing = contact_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        missing.to_string(),
+        "missing field, expected exactly one of `email`, `phone`"
+    );
+
+    let input = json!({"email": "ada@example.com", "phone": "555-0100"});
+    let both = contact_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        both.to_string(),
+        "conflicting fields `email`, `phone`, expected exactly one of `email`, `phone`"
+    );
+
+    let input = json!({"email": "ada@example.com", "nickname": "ada", "alias": "countess"});
+    let conflicting = contact_deserializer()
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        conflicting.to_string(),
+        "conflicting fields `nickname`, `alias`, expected at most one of `nickname`, `alias`"
+    );
+
+    // Names that aren't fields are reported when deserializing
+    let input = json!({"email": "ada@example.com"});
+    let unknown = StructDeserializer::new()
+        .optional_field("email")
+        .at_least_one_of(&["email", "fax"])
+        .final_builder(|email: Option<String>| email)
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        unknown.to_string(),
+        "`fax` in `at_least_one_of` isn't the name of an added field"
+    );
+
+    let input = json!({});
+    let dyn_missing = DynStructDeserializer::new()
+        .optional_field("email", DynDecoder::of::<String>())
+        .optional_field("phone", DynDecoder::of::<String>())
+        .at_least_one_of(&["email", "phone"])
+        .final_builder(|fields: DynFields| fields.contains("email"))
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        dyn_missing.to_string(),
+        "missing field, expected at least one of `email`, `phone`"
+    );
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use serde_builder::{codec::StructCodec, de::StructDeserializer};
+use serde_json::json;
+
+#[derive(Debug, PartialEq)]
+struct Reading {
+    sensor: String,
+    value: f64,
+    unit: String,
+}
+
+fn main() {
+    // fields keep their names and also match their ids, which `emit_ids` writes as keys
+    let reading_codec = || {
+        StructCodec::new()
+            .field("sensor", |r: &Reading| &r.sensor)
+            .id(1)
+            .field("value", |r: &Reading| &r.value)
+            .id(2)
+            .field("unit", |r: &Reading| &r.unit)
+            .final_builder(|sensor, value, unit| Reading {
+                sensor,
+                value,
+                unit,
+            })
+    };
+    let reading = Reading {
+        sensor: "t1".into(),
+        value: 21.5,
+        unit: "C".into(),
+    };
+
+    let by_name = reading_codec()
+        .serialize(&reading, serde_json::value::Serializer)
+        .expect("Serialization failed");
+    assert_eq!(by_name, json!({"sensor": "t1", "value": 21.5, "unit": "C"}));
+
+    // fields without an id keep using their name
+    let by_id = reading_codec()
+        .emit_ids()
+        .serialize(&reading, serde_json::value::Serializer)
+        .expect("Serialization failed");
+    assert_eq!(by_id, json!({"1": "t1", "2": 21.5, "unit": "C"}));
+
+    let mut msgpack = Vec::new();
+    reading_codec()
+        .emit_ids()
+        .serialize(&reading, &mut rmp_serde::Serializer::new(&mut msgpack))
+        .expect("Serialization failed");
+    let from_msgpack = reading_codec()
+        .deserialize(&mut rmp_serde::Deserializer::new(&msgpack[..]))
+        .expect("Deserialization failed");
+    assert_eq!(from_msgpack, reading);
+
+    let from_json = reading_codec()
+        .deserialize(&by_name)
+        .expect("Deserialization failed");
+    assert_eq!(from_json, reading);
+
+    // a field added with only an id is matched by integer keys alone
+    let mut msgpack = Vec::new();
+    rmp_serde::encode::write(
+        &mut msgpack,
+        &[(1u8, 7u32)].into_iter().collect::<BTreeMap<_, _>>(),
+    )
+    .expect("Serialization failed");
+    let id_only = StructDeserializer::new()
+        .field_id(1)
+        .final_builder(|count: u32| count)
+        .deserialize(&mut rmp_serde::Deserializer::new(&msgpack[..]))
+        .expect("Deserialization failed");
+    assert_eq!(id_only, 7);
+
+    let input = json!({"sensor": "t1"});
+    let same_id = StructDeserializer::new()
+        .field("sensor")
+        .id(1)
+        .field("unit")
+        .id(1)
+        .final_builder(|sensor: String, unit: String| (sensor, unit))
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        same_id.to_string(),
+        "fields `sensor` and `unit` have the same id 1"
+    );
+
+    let mut msgpack = Vec::new();
+    rmp_serde::encode::write(
+        &mut msgpack,
+        &[(1u8, "t1"), (9, "x")]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    )
+    .expect("Serialization failed");
+    let unknown_id = StructDeserializer::new()
+        .field("sensor")
+        .id(1)
+        .deny_unknown_fields()
+        .final_builder(|sensor: String| sensor)
+        .deserialize(&mut rmp_serde::Deserializer::new(&msgpack[..]))
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        unknown_id.to_string(),
+        "unknown field `9`, expected `sensor`"
+    );
+}
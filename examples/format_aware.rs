@@ -0,0 +1,128 @@
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+use serde_builder::{
+    de::{DeserializeWith, StructDeserializer},
+    format::{ByFormat, Plain},
+    ser::{SerializeWith, StructSerializer},
+};
+
+#[derive(Debug, PartialEq)]
+struct Theme {
+    name: String,
+    color: u32,
+}
+
+#[derive(Deserialize)]
+struct RawTheme {
+    color: u32,
+}
+
+/// Writes colors as `#rrggbb` strings
+struct Hex;
+
+impl SerializeWith<u32> for Hex {
+    fn serialize<S: Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{value:06x}"))
+    }
+}
+
+impl DeserializeWith<u32> for Hex {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let digits = hex
+            .strip_prefix('#')
+            .ok_or_else(|| D::Error::custom("color must start with `#`"))?;
+        u32::from_str_radix(digits, 16).map_err(D::Error::custom)
+    }
+}
+
+type ColorCodec = ByFormat<Hex, Plain>;
+
+fn main() {
+    let theme = Theme {
+        name: "sunset".into(),
+        color: 0xff8800,
+    };
+    let theme_serializer = || {
+        StructSerializer::new()
+            .field("name", |t: &Theme| &t.name)
+            .field_with::<ColorCodec, _, _>("color", |t: &Theme| &t.color)
+    };
+    let theme_deserializer = || {
+        StructDeserializer::new()
+            .field("name")
+            .field_with::<ColorCodec, _, _>("color")
+            .final_builder(|name, color| Theme { name, color })
+    };
+
+    let mut json = Vec::new();
+    theme_serializer()
+        .serialize(&theme, &mut serde_json::Serializer::new(&mut json))
+        .expect("Serialization failed");
+    assert_eq!(
+        String::from_utf8(json.clone()).unwrap(),
+        r##"{"name":"sunset","color":"#ff8800"}"##
+    );
+    let from_json = theme_deserializer()
+        .deserialize(&mut serde_json::Deserializer::from_slice(&json))
+        .expect("Deserialization failed");
+    assert_eq!(from_json, theme);
+
+    // MessagePack isn't human-readable, the color is written as an integer. The default
+    // serializer writes structs as arrays of their field values, read back with `visit_seq`.
+    let mut msgpack = Vec::new();
+    theme_serializer()
+        .serialize(&theme, &mut rmp_serde::Serializer::new(&mut msgpack))
+        .expect("Serialization failed");
+    let raw: (String, u32) = rmp_serde::from_slice(&msgpack).unwrap();
+    assert_eq!(raw, ("sunset".into(), 0xff8800));
+    let from_msgpack = theme_deserializer()
+        .deserialize(&mut rmp_serde::Deserializer::new(msgpack.as_slice()))
+        .expect("Deserialization failed");
+    assert_eq!(from_msgpack, theme);
+
+    // A struct map is read with `visit_map` as before
+    let mut msgpack_map = Vec::new();
+    theme_serializer()
+        .serialize(
+            &theme,
+            &mut rmp_serde::Serializer::new(&mut msgpack_map).with_struct_map(),
+        )
+        .expect("Serialization failed");
+    let raw: RawTheme = rmp_serde::from_slice(&msgpack_map).unwrap();
+    assert_eq!(raw.color, 0xff8800);
+    let from_msgpack_map = theme_deserializer()
+        .deserialize(&mut rmp_serde::Deserializer::new(msgpack_map.as_slice()))
+        .expect("Deserialization failed");
+    assert_eq!(from_msgpack_map, theme);
+
+    // Missing elements are reported with the number of fields expected
+    let short = rmp_serde::to_vec(&("sunset",)).unwrap();
+    let error = theme_deserializer()
+        .deserialize(&mut rmp_serde::Deserializer::new(short.as_slice()))
+        .unwrap_err()
+        .into_de_error();
+    assert!(error.to_string().contains("struct Theme with 2 elements"));
+
+    // Optional fields are written as their plain value, skipped fields have no element
+    let optional_deserializer = || {
+        StructDeserializer::new()
+            .field("name")
+            .optional_field("accent")
+            .skip_field(|| 1)
+            .final_builder(|name: String, accent: Option<u32>, version: u32| {
+                (name, accent, version)
+            })
+    };
+    let with_accent = rmp_serde::to_vec(&("sunset", 3)).unwrap();
+    let (name, accent, version) = optional_deserializer()
+        .deserialize(&mut rmp_serde::Deserializer::new(with_accent.as_slice()))
+        .expect("Deserialization failed");
+    assert_eq!((name.as_str(), accent, version), ("sunset", Some(3), 1));
+
+    // Like serde derive, optional fields past the end of a shorter sequence are missing
+    let without_accent = rmp_serde::to_vec(&("sunset",)).unwrap();
+    let (name, accent, version) = optional_deserializer()
+        .deserialize(&mut rmp_serde::Deserializer::new(without_accent.as_slice()))
+        .expect("Deserialization failed");
+    assert_eq!((name.as_str(), accent, version), ("sunset", None, 1));
+}
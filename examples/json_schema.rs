@@ -0,0 +1,73 @@
+use serde_builder::{
+    case::Case,
+    de::{
+        validators::{Length, OnField},
+        StructDeserializer,
+    },
+};
+
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+struct User {
+    display_name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    address: Address,
+}
+
+fn main() {
+    let address_deserializer = StructDeserializer::new()
+        .field("city")
+        .optional_field("zip")
+        .final_builder(|city, zip| Address { city, zip });
+    let user_deserializer = StructDeserializer::new()
+        .field("display_name")
+        .optional_field("email")
+        .optional_field("phone")
+        .nested_field("home_address", address_deserializer)
+        .rename_all(Case::Camel)
+        .at_least_one_of(&["email", "phone"])
+        .deny_unknown_fields()
+        .final_builder(|display_name, email, phone, address| User {
+            display_name,
+            email,
+            phone,
+            address,
+        })
+        // the constraints go to the renamed property of the field
+        .validator(OnField::new(
+            "display_name",
+            |user: &User| &user.display_name,
+            Length(1..=64),
+        ));
+
+    let schema = user_deserializer.json_schema();
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+    assert_eq!(schema.get("title").unwrap(), "User");
+    assert_eq!(
+        schema.get("required").unwrap(),
+        &serde_json::json!(["displayName", "homeAddress"])
+    );
+    assert_eq!(
+        schema.pointer("/properties/displayName/maxLength").unwrap(),
+        &serde_json::json!(64)
+    );
+    assert_eq!(
+        schema.pointer("/properties/homeAddress/required").unwrap(),
+        &serde_json::json!(["city"])
+    );
+
+    let user = user_deserializer
+        .deserialize(&mut serde_json::Deserializer::from_str(
+            r#"{"displayName": "foo", "phone": "555", "homeAddress": {"city": "Paris"}}"#,
+        ))
+        .expect("Deserialization failed");
+    assert_eq!(user.display_name, "foo");
+    assert_eq!(user.email, None);
+    assert_eq!(user.phone.as_deref(), Some("555"));
+    assert_eq!(user.address.city, "Paris");
+    assert_eq!(user.address.zip, None);
+}
@@ -0,0 +1,81 @@
+use serde_builder::{
+    case::Case,
+    de::{KeyMatching, StructDeserializer},
+};
+use serde_json::json;
+
+#[derive(Debug, PartialEq)]
+struct Account {
+    user_name: String,
+    max_sessions: u32,
+}
+
+fn main() {
+    let account_deserializer = |key_matching| {
+        StructDeserializer::new()
+            .field("user_name")
+            .field("max_sessions")
+            .key_matching(key_matching)
+            .final_builder(|user_name, max_sessions| Account {
+                user_name,
+                max_sessions,
+            })
+    };
+    let expected = Account {
+        user_name: "ada".into(),
+        max_sessions: 3,
+    };
+
+    let input = json!({"User_Name": "ada", "MAX_SESSIONS": 3});
+    let account = account_deserializer(KeyMatching::AsciiCaseInsensitive)
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(account, expected);
+
+    let input = json!({"user-name": "ada", "maxsessions": 3});
+    let account = account_deserializer(KeyMatching::IgnoreSeparators)
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(account, expected);
+
+    let input = json!({"UserName": "ada", "max-Sessions": 3});
+    let account = account_deserializer(KeyMatching::Loose)
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(account, expected);
+
+    let input = json!({"user.name": "ada", "max.sessions": 3});
+    let account = account_deserializer(KeyMatching::custom(|key| key.replace('.', "_")))
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(account, expected);
+
+    // Keys are matched against the renamed fields
+    let input = json!({"USERNAME": "ada", "maxsessions": 3});
+    let account = account_deserializer(KeyMatching::AsciiCaseInsensitive)
+        .rename_all(Case::Camel)
+        .deserialize(&input)
+        .expect("Deserialization failed");
+    assert_eq!(account, expected);
+
+    // Exact matching doesn't accept other spellings
+    let input = json!({"UserName": "ada", "max_sessions": 3});
+    let missing = account_deserializer(KeyMatching::Exact).deserialize(&input);
+    println!("{:?}", missing);
+    assert!(missing.is_err());
+
+    // Two fields normalising to the same key can't be told apart
+    let input = json!({"username": "ada"});
+    let conflict = StructDeserializer::new()
+        .field("user_name")
+        .field("username")
+        .key_matching(KeyMatching::Loose)
+        .final_builder(|first: String, second: String| (first, second))
+        .deserialize(&input)
+        .unwrap_err()
+        .into_de_error();
+    assert_eq!(
+        conflict.to_string(),
+        "fields `user_name` and `username` match the same keys"
+    );
+}
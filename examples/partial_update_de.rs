@@ -0,0 +1,59 @@
+use serde_builder::de::StructDeserializer;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Record {
+    name: String,
+    email: String,
+    age: u32,
+}
+
+fn record_deserializer() -> StructDeserializer<Record> {
+    StructDeserializer::new()
+}
+
+fn main() {
+    let mut settings_deser = serde_json::Deserializer::from_str(r#"{"name": "foobar"}"#);
+    let record = record_deserializer()
+        .setter_field("name", |r: &mut Record, name| r.name = name)
+        .setter_field("email", |r: &mut Record, email| r.email = email)
+        .setter_field("age", |r: &mut Record, age| r.age = age)
+        .from_default()
+        .deserialize(&mut settings_deser)
+        .expect("Deserialization failed");
+    assert_eq!(
+        record,
+        Record {
+            name: "foobar".into(),
+            ..Default::default()
+        }
+    );
+
+    let mut record = Record {
+        name: "foo".into(),
+        email: "foo@example.com".into(),
+        age: 20,
+    };
+    let mut patch_deser = serde_json::Deserializer::from_str(r#"{"email": "bar@example.com"}"#);
+    record_deserializer()
+        .setter_field("name", |r: &mut Record, name| r.name = name)
+        .setter_field("email", |r: &mut Record, email| r.email = email)
+        .setter_field("age", |r: &mut Record, age| r.age = age)
+        .deserialize_in_place(&mut record, &mut patch_deser)
+        .expect("Patching failed");
+    assert_eq!(record.email, "bar@example.com");
+    assert_eq!(record.name, "foo");
+
+    let mut invalid_patch_deser = serde_json::Deserializer::from_str(r#"{"age": 200}"#);
+    let patch_result = record_deserializer()
+        .setter_field("age", |r: &mut Record, age| r.age = age)
+        .validator(|r: &Record| {
+            if r.age > 150 {
+                Err(format!("age {} is out of range", r.age))
+            } else {
+                Ok(())
+            }
+        })
+        .deserialize_in_place(&mut record, &mut invalid_patch_deser);
+    println!("{:?}", patch_result);
+    assert_eq!(record.age, 20);
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use serde_builder::{de::StructDeserializer, impl_serde, ser::StructSerializer};
+
+#[derive(Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl_serde! {
+    Point {
+        deserializer: StructDeserializer::new()
+            .field("x")
+            .field("y")
+            .final_builder(|x, y| Point { x, y })
+            .validator(|point: &Point| match point.x >= 0 {
+                true => Ok(()),
+                false => Err("x must not be negative".into()),
+            }),
+        serializer: StructSerializer::new()
+            .field("x", |point: &Point| &point.x)
+            .field("y", |point: &Point| &point.y),
+    }
+}
+
+fn main() {
+    let points: Vec<Point> =
+        serde_json::from_str(r#"[{"x": 1, "y": 2}, {"x": 3, "y": 4}]"#).expect("Parsing failed");
+    assert_eq!(points, [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]);
+
+    let named: HashMap<String, Point> =
+        serde_json::from_str(r#"{"origin": {"x": 0, "y": 0}}"#).expect("Parsing failed");
+    assert_eq!(named["origin"], Point { x: 0, y: 0 });
+
+    let json = serde_json::to_string(&points).expect("Serialization failed");
+    assert_eq!(json, r#"[{"x":1,"y":2},{"x":3,"y":4}]"#);
+
+    let invalid = serde_json::from_str::<Vec<Point>>(r#"[{"x": -1, "y": 0}]"#);
+    println!("{:?}", invalid);
+    assert!(invalid.is_err());
+
+    // MessagePack writes structs as arrays by default, which isn't self-describing
+    let msgpack = rmp_serde::to_vec(&points).expect("Serialization failed");
+    let from_msgpack: Vec<Point> = rmp_serde::from_slice(&msgpack).expect("Parsing failed");
+    assert_eq!(from_msgpack, points);
+}
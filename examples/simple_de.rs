@@ -17,15 +17,28 @@ fn main() {
     });
     let test_data_string = serde_json::to_string(&test_data).unwrap();
     let mut test_data_deser = serde_json::Deserializer::from_str(&test_data_string);
-    let struct_deserializer = StructDeserializer::default()
-        .field("a_number")
-        .field("string")
-        .field("array_of_strings")
-        .final_builder(|num, string, arr| TestStruct {
-            a_number: num,
-            string,
-            array_of_strings: arr,
-        });
-    let deser_result = struct_deserializer.deserialize(&mut test_data_deser);
+    let struct_deserializer = || {
+        StructDeserializer::default()
+            .field("a_number")
+            .field("string")
+            .field("array_of_strings")
+            .final_builder(|num, string, arr| TestStruct {
+                a_number: num,
+                string,
+                array_of_strings: arr,
+            })
+    };
+    let deser_result = struct_deserializer().deserialize(&mut test_data_deser);
     println!("{:?}", deser_result);
+
+    let mut wrong_type_deser = serde_json::Deserializer::from_str("[20]");
+    let error = struct_deserializer()
+        .expecting("a test object")
+        .deserialize(&mut wrong_type_deser)
+        .unwrap_err()
+        .into_de_error();
+    assert!(
+        error.to_string().contains("expected a test object"),
+        "{error}"
+    );
 }
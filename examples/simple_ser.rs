@@ -1,6 +1,6 @@
 use std::io::Cursor;
 
-use serde_builder::ser::StructSerializer;
+use serde_builder::{case::Case, ser::StructSerializer};
 use serde_json::json;
 
 #[derive(Debug)]
@@ -36,4 +36,16 @@ fn main() {
         .expect("Deserialization of serialized data failed"),
         test_json_data
     );
+
+    // Fields added after `rename_all` are renamed as well
+    let renamed = StructSerializer::new()
+        .rename_all(Case::Camel)
+        .field("a_number", |v: &TestStruct| &v.a_number)
+        .field("array_of_strings", |v: &TestStruct| &v.array_of_strings)
+        .serialize(&test_data, serde_json::value::Serializer)
+        .expect("Serialization failed");
+    assert_eq!(
+        renamed,
+        json!({"aNumber": 42, "arrayOfStrings": ["foo", "bar", "baz"]})
+    );
 }
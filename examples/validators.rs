@@ -0,0 +1,144 @@
+use serde_builder::de::{
+    validators::{
+        CharClass, Chars, Compare, Comparison, InRange, Length, NonEmpty, OnField, Unique,
+    },
+    AllOf, And, AnyOf, Named, Or, StructDeserializer,
+};
+
+#[derive(Debug)]
+struct Booking {
+    guests: u32,
+    start: u32,
+    end: u32,
+}
+
+fn check_range(booking: &Booking) -> Result<(), String> {
+    match booking.start <= booking.end {
+        true => Ok(()),
+        false => Err(format!("{} is after {}", booking.start, booking.end)),
+    }
+}
+
+fn main() {
+    let booking_deserializer = || {
+        StructDeserializer::new()
+            .field("guests")
+            .field("start")
+            .field("end")
+            .final_builder(|guests, start, end| Booking { guests, start, end })
+    };
+    let input = r#"{"guests": 0, "start": 10, "end": 5}"#;
+
+    let error = booking_deserializer()
+        .validator(Named("range", check_range))
+        .and_validator(Named("guests", |b: &Booking| match b.guests > 0 {
+            true => Ok(()),
+            false => Err("no guests".to_string()),
+        }))
+        .deserialize(&mut serde_json::Deserializer::from_str(input))
+        .unwrap_err()
+        .into_de_error();
+    println!("{error}");
+    assert!(error.to_string().contains("range: 10 is after 5"));
+
+    let error = booking_deserializer()
+        .validator(AllOf((
+            Named("range", check_range),
+            Named("guests", |b: &Booking| match b.guests > 0 {
+                true => Ok(()),
+                false => Err("no guests".to_string()),
+            }),
+        )))
+        .deserialize(&mut serde_json::Deserializer::from_str(input))
+        .unwrap_err()
+        .into_de_error();
+    println!("{error}");
+    assert!(error
+        .to_string()
+        .contains("range: 10 is after 5; guests: no guests"));
+
+    let booking = booking_deserializer()
+        .validator(Or(check_range, |b: &Booking| match b.guests == 0 {
+            true => Ok(()),
+            false => Err("a booking with guests needs a valid range".to_string()),
+        }))
+        .deserialize(&mut serde_json::Deserializer::from_str(input))
+        .expect("Deserialization failed");
+    println!("{booking:?}");
+
+    let booking = booking_deserializer()
+        .validator(And(
+            AnyOf((check_range, |b: &Booking| match b.end == 5 {
+                true => Ok(()),
+                false => Err("doesn't end at 5".to_string()),
+            })),
+            |b: &Booking| match b.guests < 10 {
+                true => Ok(()),
+                false => Err("too many guests".to_string()),
+            },
+        ))
+        .deserialize(&mut serde_json::Deserializer::from_str(input))
+        .expect("Deserialization failed");
+    assert_eq!(booking.end, 5);
+
+    let error = booking_deserializer()
+        .validator(AllOf((
+            OnField::new("guests", |b: &Booking| &b.guests, InRange(1..=8)),
+            Compare::new(
+                "start",
+                |b: &Booking| &b.start,
+                Comparison::LessOrEqual,
+                "end",
+                |b: &Booking| &b.end,
+            ),
+        )))
+        .deserialize(&mut serde_json::Deserializer::from_str(input))
+        .unwrap_err()
+        .into_de_error();
+    println!("{error}");
+    assert!(error.to_string().contains(
+        "guests: 0 is out of range, expected at least 1 and at most 8; \
+         expected start <= end, got 10 and 5"
+    ));
+
+    let room_deserializer = || {
+        StructDeserializer::new()
+            .field("code")
+            .field("tags")
+            .final_builder(|code: String, tags: Vec<String>| (code, tags))
+            .validator(AllOf((
+                OnField::new(
+                    "code",
+                    |room: &(String, Vec<String>)| &room.0,
+                    And(
+                        Length(2..=6),
+                        Chars(&[CharClass::AsciiUppercase, CharClass::AsciiDigit]),
+                    ),
+                ),
+                OnField::new(
+                    "tags",
+                    |room: &(String, Vec<String>)| &room.1,
+                    And(NonEmpty, Unique),
+                ),
+            )))
+    };
+    let (code, tags) = room_deserializer()
+        .deserialize(&mut serde_json::Deserializer::from_str(
+            r#"{"code": "B12", "tags": ["quiet", "view"]}"#,
+        ))
+        .expect("Deserialization failed");
+    assert_eq!((code.as_str(), tags.len()), ("B12", 2));
+    let error = room_deserializer()
+        .deserialize(&mut serde_json::Deserializer::from_str(
+            r#"{"code": "b-12", "tags": ["quiet", "quiet"]}"#,
+        ))
+        .unwrap_err()
+        .into_de_error();
+    println!("{error}");
+    assert!(error
+        .to_string()
+        .contains("code: character 'b' at 0 is not allowed"));
+    assert!(error
+        .to_string()
+        .contains("tags: element at index 1 is a duplicate"));
+}
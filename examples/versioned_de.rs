@@ -0,0 +1,87 @@
+use serde_builder::de::{StructDeserializer, VersionedDeserializer};
+
+#[derive(Debug)]
+struct ConfigV1 {
+    address: String,
+}
+
+#[derive(Debug)]
+struct ConfigV2 {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    host: String,
+    port: u16,
+    workers: u32,
+}
+
+fn deserialize_config(data: &str) -> Config {
+    let mut test_data_deser = serde_json::Deserializer::from_str(data);
+    VersionedDeserializer::new("version")
+        .version(
+            1,
+            StructDeserializer::default()
+                .field("address")
+                .final_builder(|address| ConfigV1 { address }),
+        )
+        .migrate(|v1: ConfigV1| {
+            let (host, port) = v1.address.split_once(':').unwrap_or((&v1.address, "80"));
+            ConfigV2 {
+                host: host.into(),
+                port: port.parse().unwrap_or(80),
+            }
+        })
+        .version(
+            2,
+            StructDeserializer::default()
+                .field("host")
+                .field("port")
+                .final_builder(|host, port| ConfigV2 { host, port }),
+        )
+        .migrate(|v2: ConfigV2| Config {
+            host: v2.host,
+            port: v2.port,
+            workers: 1,
+        })
+        .version(
+            3,
+            StructDeserializer::default()
+                .field("host")
+                .field("port")
+                .field("workers")
+                .final_builder(|host, port, workers| Config {
+                    host,
+                    port,
+                    workers,
+                }),
+        )
+        .detect_version(|keys| keys.contains(&"address").then_some(1))
+        .deserialize(&mut test_data_deser)
+        .expect("Deserialization failed")
+}
+
+fn main() {
+    let expected = Config {
+        host: "localhost".into(),
+        port: 8080,
+        workers: 1,
+    };
+    assert_eq!(
+        deserialize_config(r#"{"version": 1, "address": "localhost:8080"}"#),
+        expected
+    );
+    assert_eq!(
+        deserialize_config(r#"{"address": "localhost:8080"}"#),
+        expected
+    );
+    assert_eq!(
+        deserialize_config(r#"{"port": 8080, "host": "localhost", "version": 2}"#),
+        expected
+    );
+    let latest =
+        deserialize_config(r#"{"version": 3, "host": "localhost", "port": 8080, "workers": 4}"#);
+    println!("{:?}", latest);
+}
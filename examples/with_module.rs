@@ -0,0 +1,53 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use serde_builder::{
+    codec::StructCodec, de::StructDeserializer, ser::StructSerializer, with_module,
+};
+
+with_module! {
+    /// Writes ranges as `{"from": .., "to": ..}`
+    mod range_codec: Range<u32> {
+        deserializer: StructDeserializer::new()
+            .field("from")
+            .field("to")
+            .final_builder(|start, end| Range { start, end }),
+        serializer: StructSerializer::new()
+            .field("from", |range: &Range<u32>| &range.start)
+            .field("to", |range: &Range<u32>| &range.end),
+    }
+}
+
+with_module! {
+    mod position_codec: (u32, u32) {
+        codec: StructCodec::new()
+            .field("line", |position: &(u32, u32)| &position.0)
+            .field("column", |position: &(u32, u32)| &position.1)
+            .final_builder(|line, column| (line, column)),
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Highlight {
+    #[serde(with = "range_codec")]
+    span: Range<u32>,
+    #[serde(
+        serialize_with = "position_codec::serialize",
+        deserialize_with = "position_codec::deserialize"
+    )]
+    start: (u32, u32),
+}
+
+fn main() {
+    let highlight = Highlight {
+        span: 4..10,
+        start: (1, 5),
+    };
+    let json = serde_json::to_string(&highlight).expect("Serialization failed");
+    assert_eq!(
+        json,
+        r#"{"span":{"from":4,"to":10},"start":{"line":1,"column":5}}"#
+    );
+    let parsed: Highlight = serde_json::from_str(&json).expect("Deserialization failed");
+    assert_eq!(parsed, highlight);
+}
@@ -0,0 +1,355 @@
+//! Derive macro for `serde_builder`, enabled with its `derive` feature
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt, parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr, Path, Type,
+};
+
+/// Most fields the builders accept
+const MAX_FIELDS: usize = 16;
+
+/// Generates `struct_deserializer()` and `struct_serializer()` returning the builders for the
+/// struct, configured from `#[serde_builder(...)]` attributes:
+///
+/// - `#[serde_builder(validator = "path")]` on the struct runs `fn(&Self) -> Result<(), String>`
+///   after deserialization
+/// - `#[serde_builder(rename = "name")]` on a field sets its key
+/// - `#[serde_builder(default)]` or `#[serde_builder(default = "path")]` on a field fills it with
+///   `Default::default()` or the result of `fn() -> T` when it is missing from the input
+/// - `#[serde_builder(with = "module")]` on a field de/serializes it with `module::serialize` and
+///   `module::deserialize`
+/// - `#[serde_builder(validator = "path")]` on a field runs `fn(&T) -> Result<(), String>` on its
+///   value after deserialization, through `OnField`. Field validators run in order before the
+///   struct's one, the first failure is reported.
+///
+/// The returned builders can still be configured further before use. The struct also implements
+/// `SerdeBuilt` with them, unless it has lifetime parameters.
+#[proc_macro_derive(Builders, attributes(serde_builder))]
+pub fn derive_builders(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    ident: Ident,
+    ty: Type,
+    name: String,
+    default: Option<FieldDefault>,
+    with: Option<Path>,
+    validator: Option<Path>,
+}
+
+enum FieldDefault {
+    Trait,
+    Path(Path),
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Builders` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Builders` can only be derived for structs with named fields",
+        ));
+    };
+    if named.named.is_empty() || named.named.len() > MAX_FIELDS {
+        return Err(Error::new_spanned(
+            &input.ident,
+            format!("`Builders` supports structs with 1 to {MAX_FIELDS} fields"),
+        ));
+    }
+
+    let validator = container_validator(&input)?;
+    let fields = named
+        .named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("named field");
+            let mut parsed = Field {
+                name: ident.unraw().to_string(),
+                ident,
+                ty: field.ty.clone(),
+                default: None,
+                with: None,
+                validator: None,
+            };
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("serde_builder"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        parsed.name = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("default") {
+                        parsed.default = Some(match meta.value() {
+                            Ok(value) => FieldDefault::Path(value.parse::<LitStr>()?.parse()?),
+                            Err(_) => FieldDefault::Trait,
+                        });
+                    } else if meta.path.is_ident("with") {
+                        parsed.with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    } else if meta.path.is_ident("validator") {
+                        parsed.validator = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    } else {
+                        return Err(meta.error("unknown field attribute"));
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(parsed)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if !input.generics.params.is_empty() {
+        if let Some(field) = fields.iter().find(|field| field.with.is_some()) {
+            return Err(Error::new_spanned(
+                &field.ident,
+                "`with` is not supported on fields of generic structs",
+            ));
+        }
+    }
+
+    let krate = quote!(::serde_builder);
+    let serde = quote!(#krate::__private::serde);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let len = fields.len();
+
+    let with_types: Vec<Option<Ident>> = fields
+        .iter()
+        .map(|field| {
+            field.with.as_ref().map(|_| {
+                format_ident!(
+                    "__{}With{}",
+                    ident,
+                    field.ident.unraw(),
+                    span = Span::call_site()
+                )
+            })
+        })
+        .collect();
+    let with_impls = fields
+        .iter()
+        .zip(&with_types)
+        .filter_map(|(field, with_type)| {
+            let (module, with_type) = (field.with.as_ref()?, with_type.as_ref()?);
+            let ty = &field.ty;
+            Some(quote! {
+                #[doc(hidden)]
+                pub struct #with_type;
+
+                impl #krate::ser::SerializeWith<#ty> for #with_type {
+                    fn serialize<__S: #serde::Serializer>(
+                        value: &#ty,
+                        serializer: __S,
+                    ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                        #module::serialize(value, serializer)
+                    }
+                }
+
+                impl #krate::de::DeserializeWith<#ty> for #with_type {
+                    fn deserialize<'de, __D: #serde::Deserializer<'de>>(
+                        deserializer: __D,
+                    ) -> ::core::result::Result<#ty, __D::Error> {
+                        #module::deserialize(deserializer)
+                    }
+                }
+            })
+        });
+
+    let mut args = Vec::new();
+    let mut decoders = Vec::new();
+    let mut de_calls = Vec::new();
+    let mut bindings = Vec::new();
+    let mut assignments = Vec::new();
+    let mut ser_fields = Vec::new();
+    let mut ser_calls = Vec::new();
+    for (i, (field, with_type)) in fields.iter().zip(&with_types).enumerate() {
+        let Field {
+            ident: field_ident,
+            ty,
+            name,
+            default,
+            ..
+        } = field;
+        let binding = format_ident!("__field{}", i);
+        let optional = default.is_some();
+        args.push(match optional {
+            true => quote!(::core::option::Option<#ty>),
+            false => quote!(#ty),
+        });
+        let (decoder, de_call) = match (with_type, optional) {
+            (None, false) => (
+                quote!(#krate::de::ValueDecoder),
+                quote!(.field::<#ty, _>(#name)),
+            ),
+            (None, true) => (
+                quote!(#krate::de::OptionalDecoder),
+                quote!(.optional_field::<#ty, _>(#name)),
+            ),
+            (Some(with_type), false) => (
+                quote!(#krate::de::WithDecoder<#with_type>),
+                quote!(.field_with::<#with_type, #ty, _>(#name)),
+            ),
+            (Some(with_type), true) => (
+                quote!(#krate::de::OptionalWithDecoder<#with_type>),
+                quote!(.optional_field_with::<#with_type, #ty, _>(#name)),
+            ),
+        };
+        decoders.push(decoder);
+        de_calls.push(de_call);
+        assignments.push(match default {
+            None => quote!(#field_ident: #binding),
+            Some(FieldDefault::Trait) => {
+                quote!(#field_ident: #binding.unwrap_or_default())
+            }
+            Some(FieldDefault::Path(path)) => quote!(#field_ident: #binding.unwrap_or_else(#path)),
+        });
+        bindings.push(binding);
+
+        let accessor = quote!(fn(&Self) -> &#ty);
+        match with_type {
+            None => {
+                ser_fields.push(quote!(#krate::ser::Field<Self, #ty, #accessor>));
+                ser_calls
+                    .push(quote!(.field::<#ty, #accessor>(#name, |value| &value.#field_ident)));
+            }
+            Some(with_type) => {
+                ser_fields.push(quote!(#krate::ser::FieldWith<Self, #ty, #accessor, #with_type>));
+                ser_calls.push(quote! {
+                    .field_with::<#with_type, #ty, #accessor>(#name, |value| &value.#field_ident)
+                });
+            }
+        }
+    }
+
+    let final_builder = quote!(fn(#(#args),*) -> Self);
+    let result = quote!(::core::result::Result<(), ::std::string::String>);
+    let field_validators = fields.iter().filter_map(|field| {
+        let Field {
+            ident: field_ident,
+            ty,
+            name,
+            validator,
+            ..
+        } = field;
+        let validator = validator.as_ref()?;
+        let validator_type =
+            quote!(#krate::de::validators::OnField<fn(&Self) -> &#ty, fn(&#ty) -> #result>);
+        let value = quote! {
+            <#validator_type>::new::<Self, #ty>(#name, |value| &value.#field_ident, #validator)
+        };
+        Some((validator_type, value))
+    });
+    let container_validator = validator.map(|path| (quote!(fn(&Self) -> #result), quote!(#path)));
+    // several validators are chained with `And`, which stops at the first failure
+    let validators: Vec<_> = field_validators.chain(container_validator).collect();
+    let (validator_type, validator_call) = match validators.into_iter().rev().reduce(
+        |(next_type, next), (validator_type, validator)| {
+            (
+                quote!(#krate::de::And<#validator_type, #next_type>),
+                quote!(#krate::de::And(#validator, #next)),
+            )
+        },
+    ) {
+        Some((validator_type, validator)) => (
+            validator_type.clone(),
+            quote!(.validator::<#validator_type>(#validator)),
+        ),
+        None => (quote!(()), quote!()),
+    };
+
+    let deserializer_type = quote! {
+        #krate::de::StructDeserializer<
+            Self,
+            (#(#args,)*),
+            (#(#decoders,)*),
+            #final_builder,
+            #validator_type,
+            (),
+            #len,
+        >
+    };
+    let serializer_type = quote!(#krate::ser::StructSerializer<Self, (#(#ser_fields,)*), #len>);
+
+    // fields borrowing from the input can't be deserialized by the builders
+    let serde_built = input.generics.lifetimes().next().is_none().then(|| {
+        let mut generics = input.generics.clone();
+        let where_clause = generics.make_where_clause();
+        for param in input.generics.type_params() {
+            let param = &param.ident;
+            where_clause.predicates.push(syn::parse_quote! {
+                #param: #serde::Serialize + #serde::de::DeserializeOwned
+            });
+        }
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics #krate::built::SerdeBuilt for #ident #ty_generics #where_clause {
+                type Deserializer = #deserializer_type;
+                type Serializer = #serializer_type;
+
+                fn deserializer() -> Self::Deserializer {
+                    Self::struct_deserializer()
+                }
+
+                fn serializer() -> Self::Serializer {
+                    Self::struct_serializer()
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        const _: () = {
+            #(#with_impls)*
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// `StructDeserializer` generated by `#[derive(Builders)]`
+                pub fn struct_deserializer() -> #deserializer_type {
+                    #krate::de::StructDeserializer::new()
+                        #(#de_calls)*
+                        .final_builder::<#final_builder>(|#(#bindings),*| Self {
+                            #(#assignments,)*
+                        })
+                        #validator_call
+                }
+
+                /// `StructSerializer` generated by `#[derive(Builders)]`
+                pub fn struct_serializer() -> #serializer_type {
+                    #krate::ser::StructSerializer::new()
+                        #(#ser_calls)*
+                }
+            }
+
+            #serde_built
+        };
+    })
+}
+
+fn container_validator(input: &DeriveInput) -> Result<Option<Path>, Error> {
+    let mut validator = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("serde_builder"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("validator") {
+                validator = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown container attribute"))
+            }
+        })?;
+    }
+    Ok(validator)
+}
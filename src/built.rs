@@ -0,0 +1,144 @@
+use serde::{Deserializer, Serializer};
+
+use crate::{
+    de::{error::Error, TypeDeserializer},
+    ser::TypeSerializer,
+};
+
+/// Type de/serialized with builders, `impl_serde!` turns them into its `Serialize` and
+/// `Deserialize` implementations
+pub trait SerdeBuilt: Sized {
+    type Deserializer: TypeDeserializer<Self>;
+    type Serializer: TypeSerializer<Self>;
+
+    fn deserializer() -> Self::Deserializer;
+
+    fn serializer() -> Self::Serializer;
+}
+
+/// Serializes `value` with its builder, usable with `#[serde(serialize_with = "...")]`
+pub fn serialize<T: SerdeBuilt, S: Serializer>(value: &T, ser: S) -> Result<S::Ok, S::Error> {
+    T::serializer().serialize(value, ser)
+}
+
+/// Deserializes a `T` with its builder, usable with `#[serde(deserialize_with = "...")]`
+pub fn deserialize<'de, T: SerdeBuilt, D: Deserializer<'de>>(des: D) -> Result<T, D::Error> {
+    T::deserializer()
+        .deserialize(des)
+        .map_err(Error::into_de_error)
+}
+
+/// Implements `Serialize` and `Deserialize` for types, either as `Type { deserializer: ...,
+/// serializer: ... }` or `Type { codec: ... }` with builder expressions evaluated on every call, or
+/// as a list of types implementing `SerdeBuilt`
+#[macro_export]
+macro_rules! impl_serde {
+    ($(
+        $ty:ty {
+            deserializer: $deserializer:expr,
+            serializer: $serializer:expr $(,)?
+        }
+    )+) => {
+        $(
+            impl $crate::__private::serde::Serialize for $ty {
+                fn serialize<S: $crate::__private::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    $crate::ser::TypeSerializer::serialize($serializer, self, serializer)
+                }
+            }
+
+            impl<'de> $crate::__private::serde::Deserialize<'de> for $ty {
+                fn deserialize<D: $crate::__private::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    $crate::de::TypeDeserializer::deserialize($deserializer, deserializer)
+                        .map_err($crate::de::error::Error::into_de_error)
+                }
+            }
+        )+
+    };
+    ($(
+        $ty:ty {
+            codec: $codec:expr $(,)?
+        }
+    )+) => {
+        $crate::impl_serde! {
+            $(
+                $ty {
+                    deserializer: $codec,
+                    serializer: $codec,
+                }
+            )+
+        }
+    };
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl $crate::__private::serde::Serialize for $ty {
+                fn serialize<S: $crate::__private::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    $crate::built::serialize(self, serializer)
+                }
+            }
+
+            impl<'de> $crate::__private::serde::Deserialize<'de> for $ty {
+                fn deserialize<D: $crate::__private::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    $crate::built::deserialize(deserializer)
+                }
+            }
+        )+
+    };
+}
+
+/// Generates a module with `serialize` and `deserialize` functions built from a serializer and a
+/// deserializer, or from a `StructCodec`, for use with serde derive's `with`, `serialize_with` and
+/// `deserialize_with` attributes. The builders are created again on every call.
+#[macro_export]
+macro_rules! with_module {
+    (
+        $(#[$attr:meta])*
+        $vis:vis mod $name:ident: $ty:ty {
+            deserializer: $deserializer:expr,
+            serializer: $serializer:expr $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            pub fn serialize<S: $crate::__private::serde::Serializer>(
+                value: &$ty,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error> {
+                $crate::ser::TypeSerializer::serialize($serializer, value, serializer)
+            }
+
+            pub fn deserialize<'de, D: $crate::__private::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::core::result::Result<$ty, D::Error> {
+                $crate::de::TypeDeserializer::deserialize($deserializer, deserializer)
+                    .map_err($crate::de::error::Error::into_de_error)
+            }
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis mod $name:ident: $ty:ty {
+            codec: $codec:expr $(,)?
+        }
+    ) => {
+        $crate::with_module! {
+            $(#[$attr])*
+            $vis mod $name: $ty {
+                deserializer: $codec,
+                serializer: $codec,
+            }
+        }
+    };
+}
@@ -0,0 +1,305 @@
+use serde::{Deserializer, Serializer};
+
+use crate::{
+    case::Case,
+    de::{
+        error::Error, And, DeserializeWith, DuplicateFields, FieldDecoders, FinalBuilder,
+        KeyMatching, StructDeserializer, TypeDeserializer, Validator, ValueDecoder, WithDecoder,
+    },
+    ser::{Field, FieldWith, SerializeWith, SerializerField, StructSerializer, TypeSerializer},
+};
+
+/// Builder declaring each field once with its name and accessor, producing a `StructDeserializer`
+/// and a `StructSerializer` that always agree on the keys
+pub struct StructCodec<
+    T,
+    FBARGS = (),
+    FDS = (),
+    FIELDS = (),
+    FB = (),
+    V: Validator<T> = (),
+    const FN: usize = 0,
+> {
+    deserializer: StructDeserializer<T, FBARGS, FDS, FB, V, (), FN>,
+    serializer: StructSerializer<T, FIELDS, FN>,
+}
+
+impl<T> Default for StructCodec<T> {
+    fn default() -> Self {
+        Self {
+            deserializer: StructDeserializer::new(),
+            serializer: StructSerializer::new(),
+        }
+    }
+}
+
+impl<T> StructCodec<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, FBARGS, FDS, FIELDS, FB, V: Validator<T>, const FN: usize>
+    StructCodec<T, FBARGS, FDS, FIELDS, FB, V, FN>
+{
+    /// Adds a validator running after the ones already set, see `And`
+    pub fn and_validator<W: Validator<T>>(
+        self,
+        validator: W,
+    ) -> StructCodec<T, FBARGS, FDS, FIELDS, FB, And<V, W>, FN> {
+        let StructCodec {
+            deserializer,
+            serializer,
+        } = self;
+        StructCodec {
+            deserializer: deserializer.and_validator(validator),
+            serializer,
+        }
+    }
+
+    /// Name of the struct on both sides, the type name without its path by default
+    pub fn name(self, name: &'static str) -> Self {
+        let StructCodec {
+            deserializer,
+            serializer,
+        } = self;
+        StructCodec {
+            deserializer: deserializer.name(name),
+            serializer: serializer.name(name),
+        }
+    }
+
+    /// What the input should be, see `StructDeserializer::expecting`
+    pub fn expecting<E: ToString>(mut self, expecting: E) -> Self {
+        self.deserializer = self.deserializer.expecting(expecting);
+        self
+    }
+
+    /// Renames the keys of both sides
+    pub fn rename_all(self, case: Case) -> Self {
+        let StructCodec {
+            deserializer,
+            serializer,
+        } = self;
+        StructCodec {
+            deserializer: deserializer.rename_all(case),
+            serializer: serializer.rename_all(case),
+        }
+    }
+
+    pub fn key_matching(mut self, key_matching: KeyMatching) -> Self {
+        self.deserializer = self.deserializer.key_matching(key_matching);
+        self
+    }
+
+    /// Sets the duplicate policy for fields that don't have their own set with `on_duplicate`
+    pub fn duplicate_fields(mut self, duplicate_fields: DuplicateFields) -> Self {
+        self.deserializer = self.deserializer.duplicate_fields(duplicate_fields);
+        self
+    }
+
+    /// Reject keys that don't belong to any field instead of ignoring them
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.deserializer = self.deserializer.deny_unknown_fields();
+        self
+    }
+
+    /// Requires exactly one of the named fields to be present in the input
+    pub fn exactly_one_of(mut self, names: &[&str]) -> Self {
+        self.deserializer = self.deserializer.exactly_one_of(names);
+        self
+    }
+
+    /// Rejects the input if more than one of the named fields is present
+    pub fn at_most_one_of(mut self, names: &[&str]) -> Self {
+        self.deserializer = self.deserializer.at_most_one_of(names);
+        self
+    }
+
+    /// Requires at least one of the named fields to be present in the input
+    pub fn at_least_one_of(mut self, names: &[&str]) -> Self {
+        self.deserializer = self.deserializer.at_least_one_of(names);
+        self
+    }
+
+    /// Assigns a numeric id to the last added field on both sides, emitted as its key when
+    /// `emit_ids` is set
+    pub fn id(self, id: u64) -> Self {
+        let StructCodec {
+            deserializer,
+            serializer,
+        } = self;
+        StructCodec {
+            deserializer: deserializer.id(id),
+            serializer: serializer.id(id),
+        }
+    }
+
+    /// Sets the duplicate policy of the last added field
+    pub fn on_duplicate(mut self, duplicate_fields: DuplicateFields) -> Self {
+        self.deserializer = self.deserializer.on_duplicate(duplicate_fields);
+        self
+    }
+
+    /// Serializes the value as a map keyed by field ids, see `StructSerializer::emit_ids`
+    pub fn emit_ids(mut self) -> Self {
+        self.serializer = self.serializer.emit_ids();
+        self
+    }
+
+    /// Splits the codec into its deserializer and serializer
+    pub fn into_parts(
+        self,
+    ) -> (
+        StructDeserializer<T, FBARGS, FDS, FB, V, (), FN>,
+        StructSerializer<T, FIELDS, FN>,
+    ) {
+        (self.deserializer, self.serializer)
+    }
+}
+
+impl<T, FBARGS, FDS, FIELDS, V: Validator<T>, const FN: usize>
+    StructCodec<T, FBARGS, FDS, FIELDS, (), V, FN>
+{
+    pub fn final_builder<FB: FinalBuilder<T, FBARGS>>(
+        self,
+        final_builder: FB,
+    ) -> StructCodec<T, FBARGS, FDS, FIELDS, FB, V, FN> {
+        let StructCodec {
+            deserializer,
+            serializer,
+        } = self;
+        StructCodec {
+            deserializer: deserializer.final_builder(final_builder),
+            serializer,
+        }
+    }
+}
+
+impl<T, FBARGS, FDS, FIELDS, FB, const FN: usize> StructCodec<T, FBARGS, FDS, FIELDS, FB, (), FN> {
+    pub fn validator<V: Validator<T>>(
+        self,
+        validator: V,
+    ) -> StructCodec<T, FBARGS, FDS, FIELDS, FB, V, FN> {
+        let StructCodec {
+            deserializer,
+            serializer,
+        } = self;
+        StructCodec {
+            deserializer: deserializer.validator(validator),
+            serializer,
+        }
+    }
+}
+
+impl<T, FBARGS, FDS, FIELDS, FB, V, const FN: usize> StructCodec<T, FBARGS, FDS, FIELDS, FB, V, FN>
+where
+    FDS: FieldDecoders<FBARGS>,
+    FB: FinalBuilder<T, FBARGS>,
+    V: Validator<T>,
+{
+    pub fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        self.deserializer.deserialize(des)
+    }
+}
+
+impl<T, FBARGS, FDS, FIELDS, FB, V, const FN: usize> TypeDeserializer<T>
+    for StructCodec<T, FBARGS, FDS, FIELDS, FB, V, FN>
+where
+    FDS: FieldDecoders<FBARGS>,
+    FB: FinalBuilder<T, FBARGS>,
+    V: Validator<T>,
+{
+    fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        self.deserializer.deserialize(des)
+    }
+}
+
+macro_rules! add_field_impl {
+    ($($len:expr => ($($n:tt $name1:ident $dname1:ident $sname1:ident),*))+) => {
+        $(
+            impl<T, $($name1, $dname1, $sname1,)* V: Validator<T>> StructCodec<T, ($($name1,)*), ($($dname1,)*), ($($sname1,)*), (), V, $len> {
+                /// Adds a field read with its `Deserialize` implementation and written from the
+                /// reference returned by `field_accessor`
+                pub fn field<FT, FA: FnOnce(&T) -> &FT>(self, name: &'static str, field_accessor: FA) -> StructCodec<T, ($($name1,)* FT,), ($($dname1,)* ValueDecoder,), ($($sname1,)* Field<T, FT, FA>,), (), V, {$len+1}> {
+                    let StructCodec {
+                        deserializer,
+                        serializer,
+                    } = self;
+                    StructCodec {
+                        deserializer: deserializer.field(name),
+                        serializer: serializer.field(name, field_accessor),
+                    }
+                }
+
+                /// Adds a field de/serialized with `W` instead of its serde implementations
+                pub fn field_with<W: SerializeWith<FT> + DeserializeWith<FT>, FT, FA: FnOnce(&T) -> &FT>(self, name: &'static str, field_accessor: FA) -> StructCodec<T, ($($name1,)* FT,), ($($dname1,)* WithDecoder<W>,), ($($sname1,)* FieldWith<T, FT, FA, W>,), (), V, {$len+1}> {
+                    let StructCodec {
+                        deserializer,
+                        serializer,
+                    } = self;
+                    StructCodec {
+                        deserializer: deserializer.field_with::<W, FT, _>(name),
+                        serializer: serializer.field_with::<W, FT, FA>(name, field_accessor),
+                    }
+                }
+            }
+        )+
+    }
+}
+
+add_field_impl! {
+    0 => ()
+    1 => (0 T0 D0 F0)
+    2 => (0 T0 D0 F0, 1 T1 D1 F1)
+    3 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2)
+    4 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3)
+    5 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4)
+    6 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5)
+    7 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6)
+    8 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7)
+    9 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8)
+    10 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8, 9 T9 D9 F9)
+    11 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8, 9 T9 D9 F9, 10 T10 D10 F10)
+    12 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8, 9 T9 D9 F9, 10 T10 D10 F10, 11 T11 D11 F11)
+    13 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8, 9 T9 D9 F9, 10 T10 D10 F10, 11 T11 D11 F11, 12 T12 D12 F12)
+    14 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8, 9 T9 D9 F9, 10 T10 D10 F10, 11 T11 D11 F11, 12 T12 D12 F12, 13 T13 D13 F13)
+    15 => (0 T0 D0 F0, 1 T1 D1 F1, 2 T2 D2 F2, 3 T3 D3 F3, 4 T4 D4 F4, 5 T5 D5 F5, 6 T6 D6 F6, 7 T7 D7 F7, 8 T8 D8 F8, 9 T9 D9 F9, 10 T10 D10 F10, 11 T11 D11 F11, 12 T12 D12 F12, 13 T13 D13 F13, 14 T14 D14 F14)
+}
+
+macro_rules! ser_impl {
+    ($($len:expr => ($($sname:ident),+))+) => {
+        $(
+            impl<T, FBARGS, FDS, $($sname: SerializerField<T>,)+ FB, V: Validator<T>> StructCodec<T, FBARGS, FDS, ($($sname,)+), FB, V, $len> {
+                pub fn serialize<S: Serializer>(self, value: &T, ser: S) -> Result<S::Ok, S::Error> {
+                    self.serializer.serialize(value, ser)
+                }
+            }
+
+            impl<T, FBARGS, FDS, $($sname: SerializerField<T>,)+ FB, V: Validator<T>> TypeSerializer<T> for StructCodec<T, FBARGS, FDS, ($($sname,)+), FB, V, $len> {
+                fn serialize<S: Serializer>(self, value: &T, ser: S) -> Result<S::Ok, S::Error> {
+                    self.serializer.serialize(value, ser)
+                }
+            }
+        )+
+    }
+}
+
+ser_impl! {
+    1 => (F0)
+    2 => (F0, F1)
+    3 => (F0, F1, F2)
+    4 => (F0, F1, F2, F3)
+    5 => (F0, F1, F2, F3, F4)
+    6 => (F0, F1, F2, F3, F4, F5)
+    7 => (F0, F1, F2, F3, F4, F5, F6)
+    8 => (F0, F1, F2, F3, F4, F5, F6, F7)
+    9 => (F0, F1, F2, F3, F4, F5, F6, F7, F8)
+    10 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9)
+    11 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10)
+    12 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11)
+    13 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12)
+    14 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13)
+    15 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14)
+    16 => (F0, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15)
+}
@@ -0,0 +1,235 @@
+use std::any::type_name;
+
+use crate::{
+    description::{FieldDescription, Presence, StructDescription},
+    short_type_name,
+};
+
+use super::{
+    CollectDecoder, ComputedDecoder, ConditionalDecoder, ContextDecoder, FieldUpdate,
+    NestedDecoder, OptionalDecoder, OptionalWithDecoder, SetterDecoder, SkipDecoder,
+    StructDeserializer, Validator, ValueDecoder, WithDecoder,
+};
+
+/// Describes the field a decoder reads for `FT`
+pub trait DescribeField<FT> {
+    /// Type of the value read from the input
+    fn type_name(&self) -> &'static str;
+
+    fn presence(&self) -> Presence;
+
+    fn nested(&self) -> Option<StructDescription> {
+        None
+    }
+}
+
+impl<FT> DescribeField<FT> for ValueDecoder {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Required
+    }
+}
+
+impl<FT> DescribeField<Option<FT>> for OptionalDecoder {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Optional
+    }
+}
+
+impl<FT, W> DescribeField<FT> for WithDecoder<W> {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Required
+    }
+}
+
+impl<FT, W> DescribeField<Option<FT>> for OptionalWithDecoder<W> {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Optional
+    }
+}
+
+/// The condition is kept by the deserializer, which reports it instead
+impl<FT, F, D: DescribeField<Option<FT>>> DescribeField<Option<FT>> for ConditionalDecoder<F, D> {
+    fn type_name(&self) -> &'static str {
+        self.decoder.type_name()
+    }
+
+    fn presence(&self) -> Presence {
+        self.decoder.presence()
+    }
+}
+
+impl<FT> DescribeField<Vec<FT>> for CollectDecoder {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Optional
+    }
+}
+
+impl<FT, F> DescribeField<FT> for SkipDecoder<F> {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Skipped
+    }
+}
+
+impl<FT, F> DescribeField<FT> for ComputedDecoder<F> {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Skipped
+    }
+}
+
+impl<FT, F> DescribeField<FieldUpdate<FT, F>> for SetterDecoder<F> {
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Optional
+    }
+}
+
+impl<FT, F, R> DescribeField<FT> for ContextDecoder<F, R> {
+    fn type_name(&self) -> &'static str {
+        type_name::<R>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Required
+    }
+}
+
+impl<FT, FBARGS, FDS, FB, V, C, const FN: usize> DescribeField<FT>
+    for NestedDecoder<StructDeserializer<FT, FBARGS, FDS, FB, V, C, FN>>
+where
+    FDS: DescribeFields<FBARGS>,
+    V: Validator<FT, C>,
+{
+    fn type_name(&self) -> &'static str {
+        type_name::<FT>()
+    }
+
+    fn presence(&self) -> Presence {
+        Presence::Required
+    }
+
+    fn nested(&self) -> Option<StructDescription> {
+        self.0.as_ref().map(StructDeserializer::describe)
+    }
+}
+
+/// Tuple of field decoders that can describe the fields producing `ARGS`
+pub trait DescribeFields<ARGS> {
+    /// Descriptions of the fields, without the names and ids kept by the deserializer
+    fn describe_fields(&self) -> Vec<FieldDescription>;
+}
+
+impl DescribeFields<()> for () {
+    fn describe_fields(&self) -> Vec<FieldDescription> {
+        Vec::new()
+    }
+}
+
+fn describe_field<FT, D: DescribeField<FT>>(decoder: &D) -> FieldDescription {
+    FieldDescription {
+        name: None,
+        id: None,
+        type_name: decoder.type_name(),
+        presence: decoder.presence(),
+        repeatable: false,
+        nested: decoder.nested(),
+    }
+}
+
+macro_rules! describe_fields_impl {
+    ($($len:expr => ($($n:tt $name:ident $dtype:ident)+))+) => {
+        $(
+            impl<$($name, $dtype: DescribeField<$name>,)+> DescribeFields<($($name,)+)> for ($($dtype,)+) {
+                fn describe_fields(&self) -> Vec<FieldDescription> {
+                    vec![$(describe_field::<$name, $dtype>(&self.$n),)+]
+                }
+            }
+        )+
+    }
+}
+
+describe_fields_impl! {
+    1 => (0 T0 D0)
+    2 => (0 T0 D0 1 T1 D1)
+    3 => (0 T0 D0 1 T1 D1 2 T2 D2)
+    4 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3)
+    5 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4)
+    6 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5)
+    7 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6)
+    8 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7)
+    9 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8)
+    10 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9)
+    11 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10)
+    12 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11)
+    13 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12)
+    14 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12 13 T13 D13)
+    15 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12 13 T13 D13 14 T14 D14)
+    16 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12 13 T13 D13 14 T14 D14 15 T15 D15)
+}
+
+impl<T, FBARGS, FDS, FB, V, C, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, V, C, FN>
+where
+    FDS: DescribeFields<FBARGS>,
+    V: Validator<T, C>,
+{
+    /// Description of the fields the builder reads, in the order they were added
+    pub fn describe(&self) -> StructDescription {
+        let fields = self
+            .decoders
+            .describe_fields()
+            .into_iter()
+            .zip(&self.fields)
+            .map(|(description, field)| FieldDescription {
+                name: field
+                    .name
+                    .as_deref()
+                    .map(|name| match self.options.rename_all {
+                        Some(case) => case.apply(name),
+                        None => name.to_owned(),
+                    }),
+                id: field.id,
+                presence: match &field.requirement {
+                    Some(requirement) => Presence::Conditional(requirement.clone()),
+                    None => description.presence,
+                },
+                repeatable: field.repeatable,
+                ..description
+            })
+            .collect();
+        StructDescription {
+            name: self.options.name.unwrap_or_else(short_type_name::<T>),
+            type_name: type_name::<T>(),
+            fields,
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use std::{any::Any, collections::HashMap, fmt, marker::PhantomData};
+
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, Error as _, IgnoredAny, MapAccess, Visitor},
+    Deserializer,
+};
+
+use super::{
+    content::{Content, ContentDeserializer, ContentError},
+    error::Error,
+    field_group::{FieldGroup, GroupKind},
+    field_key::{FieldKeySeed, FieldTable},
+    field_spec::{FieldSpec, Occurrence},
+    path::{self, Path, PathScope, Segment, TrackedSeed},
+    And, DuplicateFields, FinalBuilder, KeyMatching, Options, Validator,
+};
+use crate::{case::Case, short_type_name};
+
+type DecodeFn<'a> = Box<dyn Fn(ContentDeserializer) -> Result<Box<dyn Any>, ContentError> + 'a>;
+
+/// Type-erased decoder of the value of a field registered at runtime
+pub struct DynDecoder<'a>(DecodeFn<'a>);
+
+impl<'a> DynDecoder<'a> {
+    /// Decodes the value as `FT`
+    pub fn of<FT: DeserializeOwned + 'static>() -> Self {
+        Self::map(|value: FT| value)
+    }
+
+    /// Decodes the value as `FT` and converts it with `convert`
+    pub fn map<FT: DeserializeOwned, U: 'static, F: Fn(FT) -> U + 'a>(convert: F) -> Self {
+        Self(Box::new(move |content| {
+            let value = TrackedSeed(PhantomData::<FT>).deserialize(content)?;
+            Ok(Box::new(convert(value)))
+        }))
+    }
+}
+
+enum Presence<'a> {
+    Required,
+    Optional,
+    Default(Box<dyn Fn() -> Box<dyn Any> + 'a>),
+}
+
+struct DynField<'a> {
+    name: String,
+    decoder: DynDecoder<'a>,
+    presence: Presence<'a>,
+}
+
+/// Values of the fields of a `DynStructDeserializer`, handed to its final builder
+#[derive(Debug, Default)]
+pub struct DynFields {
+    values: HashMap<String, Box<dyn Any>>,
+}
+
+impl DynFields {
+    /// Value of the field registered as `name`, `None` if it is absent or not a `FT`
+    pub fn get<FT: 'static>(&self, name: &str) -> Option<&FT> {
+        self.values.get(name)?.downcast_ref()
+    }
+
+    /// Moves the value of the field registered as `name` out of the bag
+    pub fn take<FT: 'static>(&mut self, name: &str) -> Option<FT> {
+        if !self.values.get(name)?.is::<FT>() {
+            return None;
+        }
+        let value = self.values.remove(name)?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+}
+
+/// Deserializer for structs whose fields are only known at runtime, e.g. from a plugin manifest
+///
+/// Every field value is buffered before being decoded, so the input has to come from a
+/// self-describing format. The final builder receives the decoded values as `DynFields`.
+pub struct DynStructDeserializer<'a, T, FB = (), V: Validator<T> = ()> {
+    target_phantom: PhantomData<T>,
+    final_builder: FB,
+    validator: V,
+    fields: Vec<FieldSpec>,
+    dyn_fields: Vec<DynField<'a>>,
+    options: Options,
+}
+
+impl<'a, T> Default for DynStructDeserializer<'a, T> {
+    fn default() -> Self {
+        Self {
+            target_phantom: PhantomData,
+            final_builder: (),
+            validator: (),
+            fields: Vec::new(),
+            dyn_fields: Vec::new(),
+            options: Options::default(),
+        }
+    }
+}
+
+impl<'a, T> DynStructDeserializer<'a, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a, T, FB, V: Validator<T>> DynStructDeserializer<'a, T, FB, V> {
+    /// Adds a validator running after the ones already set, see `And`
+    pub fn and_validator<W: Validator<T>>(
+        self,
+        validator: W,
+    ) -> DynStructDeserializer<'a, T, FB, And<V, W>> {
+        let DynStructDeserializer {
+            target_phantom,
+            final_builder,
+            validator: previous,
+            fields,
+            dyn_fields,
+            options,
+        } = self;
+        DynStructDeserializer {
+            target_phantom,
+            final_builder,
+            validator: And(previous, validator),
+            fields,
+            dyn_fields,
+            options,
+        }
+    }
+
+    /// Name of the struct reported in errors, the type name without its path by default
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.options.name = Some(name);
+        self
+    }
+
+    /// What the input should be, reported when it has the wrong type, `struct <name>` by default
+    pub fn expecting<E: ToString>(mut self, expecting: E) -> Self {
+        self.options.expecting = Some(expecting.to_string());
+        self
+    }
+
+    pub fn rename_all(mut self, case: Case) -> Self {
+        self.options.rename_all = Some(case);
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    pub fn key_matching(mut self, key_matching: KeyMatching) -> Self {
+        self.options.key_matching = key_matching;
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    /// Sets the duplicate policy for fields that don't have their own set with `on_duplicate`
+    pub fn duplicate_fields(mut self, duplicate_fields: DuplicateFields) -> Self {
+        self.options.duplicate_fields = duplicate_fields;
+        self
+    }
+
+    /// Reject keys that don't belong to any field instead of ignoring them
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.options.deny_unknown_fields = true;
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    /// Requires exactly one of the named fields to be present in the input
+    pub fn exactly_one_of(mut self, names: &[&str]) -> Self {
+        self.options
+            .add_group(GroupKind::Exactly, names, &self.fields);
+        self
+    }
+
+    /// Rejects the input if more than one of the named fields is present
+    pub fn at_most_one_of(mut self, names: &[&str]) -> Self {
+        self.options
+            .add_group(GroupKind::AtMost, names, &self.fields);
+        self
+    }
+
+    /// Requires at least one of the named fields to be present in the input
+    pub fn at_least_one_of(mut self, names: &[&str]) -> Self {
+        self.options
+            .add_group(GroupKind::AtLeast, names, &self.fields);
+        self
+    }
+
+    /// Adds a field that must be present in the input
+    pub fn field<N: ToString>(self, name: N, decoder: DynDecoder<'a>) -> Self {
+        self.push_field(name.to_string(), decoder, Presence::Required)
+    }
+
+    /// Adds a field that is left out of the `DynFields` when absent from the input
+    pub fn optional_field<N: ToString>(self, name: N, decoder: DynDecoder<'a>) -> Self {
+        self.push_field(name.to_string(), decoder, Presence::Optional)
+    }
+
+    /// Adds a field that takes the value returned by `default` when absent from the input
+    pub fn default_field<N: ToString, FT: 'static, F: Fn() -> FT + 'a>(
+        self,
+        name: N,
+        decoder: DynDecoder<'a>,
+        default: F,
+    ) -> Self {
+        let default = Presence::Default(Box::new(move || Box::new(default())));
+        self.push_field(name.to_string(), decoder, default)
+    }
+
+    /// Assigns a numeric id to the last added field, matched against integer keys
+    pub fn id(self, id: u64) -> Self {
+        self.update_last_field("id", |field| field.id = Some(id))
+    }
+
+    /// Sets the duplicate policy of the last added field
+    pub fn on_duplicate(self, duplicate_fields: DuplicateFields) -> Self {
+        self.update_last_field("on_duplicate", |field| {
+            field.duplicates = Some(duplicate_fields)
+        })
+    }
+
+    fn push_field(mut self, name: String, decoder: DynDecoder<'a>, presence: Presence<'a>) -> Self {
+        self.fields.push(FieldSpec::named(name.clone()));
+        self.dyn_fields.push(DynField {
+            name,
+            decoder,
+            presence,
+        });
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    /// Applies `update` to the last added field, or records an error for `deserialize` to report
+    /// when there is none
+    fn update_last_field(mut self, method: &str, update: impl FnOnce(&mut FieldSpec)) -> Self {
+        match self.fields.last_mut() {
+            Some(field) => {
+                update(field);
+                self.options.refresh_field_table(&self.fields);
+            }
+            None => self
+                .options
+                .errors
+                .push(format!("`{method}` was called before any field was added")),
+        }
+        self
+    }
+}
+
+impl<'a, T, V: Validator<T>> DynStructDeserializer<'a, T, (), V> {
+    pub fn final_builder<FB: FinalBuilder<T, (DynFields,)>>(
+        self,
+        final_builder: FB,
+    ) -> DynStructDeserializer<'a, T, FB, V> {
+        let DynStructDeserializer {
+            target_phantom,
+            final_builder: _,
+            validator,
+            fields,
+            dyn_fields,
+            mut options,
+        } = self;
+        options.build_field_table(&fields);
+        DynStructDeserializer {
+            target_phantom,
+            final_builder,
+            validator,
+            fields,
+            dyn_fields,
+            options,
+        }
+    }
+}
+
+impl<'a, T, FB> DynStructDeserializer<'a, T, FB, ()> {
+    pub fn validator<V: Validator<T>>(self, validator: V) -> DynStructDeserializer<'a, T, FB, V> {
+        let DynStructDeserializer {
+            target_phantom,
+            final_builder,
+            validator: _,
+            fields,
+            dyn_fields,
+            options,
+        } = self;
+        DynStructDeserializer {
+            target_phantom,
+            final_builder,
+            validator,
+            fields,
+            dyn_fields,
+            options,
+        }
+    }
+}
+
+impl<'a, T, FB: FinalBuilder<T, (DynFields,)>, V: Validator<T>>
+    DynStructDeserializer<'a, T, FB, V>
+{
+    pub fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        let DynStructDeserializer {
+            target_phantom: _,
+            final_builder,
+            validator,
+            mut fields,
+            dyn_fields,
+            options:
+                Options {
+                    name,
+                    expecting,
+                    rename_all,
+                    key_matching,
+                    duplicate_fields,
+                    deny_unknown_fields,
+                    groups,
+                    errors,
+                    field_table,
+                },
+        } = self;
+        if let Some(error) = errors.into_iter().next() {
+            return Err(Error::Deserialization(
+                D::Error::custom(error),
+                Path::default(),
+            ));
+        }
+        if let Some(case) = rename_all {
+            for field in &mut fields {
+                field.name = field.name.as_deref().map(|name| case.apply(name));
+            }
+        }
+        let field_table = field_table.unwrap_or_else(|| {
+            FieldTable::new(&fields, rename_all, &key_matching, deny_unknown_fields)
+        });
+        if let Some(conflict) = field_table.conflict() {
+            return Err(Error::Deserialization(
+                D::Error::custom(conflict),
+                Path::default(),
+            ));
+        }
+        let visitor = DynFieldVisitor {
+            human_readable: des.is_human_readable(),
+            fields,
+            field_table,
+            key_matching,
+            dyn_fields,
+            duplicate_fields,
+            groups,
+            name: name.unwrap_or_else(short_type_name::<T>),
+            expecting,
+        };
+        let path_scope = PathScope::enter();
+        let args = des
+            .deserialize_map(visitor)
+            .map_err(|e| Error::Deserialization(e, path_scope.finish()))?;
+        let value = final_builder.assemble((args,), &mut ());
+        path_scope.reset();
+        validator
+            .validate(&value, &mut ())
+            .map_err(|e| Error::Validation(e, path_scope.finish()))?;
+        Ok(value)
+    }
+}
+
+struct DynFieldVisitor<'a> {
+    human_readable: bool,
+    fields: Vec<FieldSpec>,
+    field_table: FieldTable,
+    key_matching: KeyMatching,
+    dyn_fields: Vec<DynField<'a>>,
+    duplicate_fields: DuplicateFields,
+    groups: Vec<FieldGroup>,
+    name: &'static str,
+    expecting: Option<String>,
+}
+
+impl<'de, 'a> Visitor<'de> for DynFieldVisitor<'a> {
+    type Value = DynFields;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.expecting {
+            Some(expecting) => formatter.write_str(expecting),
+            None => write!(formatter, "struct {}", self.name),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let DynFieldVisitor {
+            human_readable,
+            fields,
+            field_table,
+            key_matching,
+            dyn_fields,
+            duplicate_fields,
+            groups,
+            name: _,
+            expecting: _,
+        } = self;
+        let mut values: Vec<Option<Box<dyn Any>>> = dyn_fields.iter().map(|_| None).collect();
+        let path_mark = path::mark();
+
+        while let Some(index) = map.next_key_seed(FieldKeySeed {
+            table: &field_table,
+            key_matching: &key_matching,
+        })? {
+            let Some(index) = index else {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+            let field = &fields[index];
+            match field.occurrence(values[index].is_some(), duplicate_fields) {
+                Occurrence::Decode => {}
+                Occurrence::Ignore => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+                Occurrence::Duplicate => {
+                    return Err(A::Error::duplicate_field(field.static_name(index)))
+                }
+            }
+            path::reset(path_mark);
+            let value = map
+                .next_value::<Content>()
+                .and_then(|content| {
+                    (dyn_fields[index].decoder.0)(ContentDeserializer::new(content, human_readable))
+                        .map_err(A::Error::custom)
+                })
+                .inspect_err(|_| path::insert(path_mark, Segment::Field(field.label())))?;
+            values[index] = Some(value);
+        }
+
+        let seen: Vec<bool> = values.iter().map(Option::is_some).collect();
+        for group in &groups {
+            group.check(&seen, &fields).map_err(A::Error::custom)?;
+        }
+
+        let mut bag = DynFields::default();
+        for (index, (dyn_field, value)) in dyn_fields.into_iter().zip(values).enumerate() {
+            let value = match (value, dyn_field.presence) {
+                (Some(value), _) => value,
+                (None, Presence::Required) => {
+                    return Err(A::Error::custom(format_args!(
+                        "missing field `{}`",
+                        fields[index].label()
+                    )))
+                }
+                (None, Presence::Optional) => continue,
+                (None, Presence::Default(default)) => default(),
+            };
+            bag.values.insert(dyn_field.name, value);
+        }
+        Ok(bag)
+    }
+}
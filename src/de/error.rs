@@ -1,12 +1,39 @@
 use std::fmt::Debug;
 
-use serde::Deserializer;
+use serde::{de::Error as _, Deserializer};
 use thiserror::Error;
 
+use super::Path;
+
+/// Error of a builder's `deserialize`, with the path of the value that failed
 #[derive(Error)]
 pub enum Error<'de, D: Deserializer<'de>> {
-    Deserialization(D::Error),
-    Validation(String),
+    Deserialization(D::Error, Path),
+    Validation(String, Path),
+}
+
+impl<'de, D: Deserializer<'de>> Error<'de, D> {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Deserialization(_, path) | Self::Validation(_, path) => path,
+        }
+    }
+
+    /// Converts to the deserializer's error, as needed inside `Deserialize` implementations.
+    /// Validation errors become custom errors and the path is dropped.
+    pub fn into_de_error(self) -> D::Error {
+        match self {
+            Self::Deserialization(e, _) => e,
+            Self::Validation(e, _) => D::Error::custom(e),
+        }
+    }
+
+    pub(crate) fn at(self, path: Path) -> Self {
+        match self {
+            Self::Deserialization(e, _) => Self::Deserialization(e, path),
+            Self::Validation(e, _) => Self::Validation(e, path),
+        }
+    }
 }
 
 impl<'de, D> Debug for Error<'de, D>
@@ -16,8 +43,16 @@ where
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Deserialization(deerr) => f.debug_tuple("Deserialization").field(&deerr).finish(),
-            Self::Validation(vaerr) => f.debug_tuple("Validation").field(&vaerr).finish(),
+            Self::Deserialization(deerr, path) => f
+                .debug_tuple("Deserialization")
+                .field(&deerr)
+                .field(&path)
+                .finish(),
+            Self::Validation(vaerr, path) => f
+                .debug_tuple("Validation")
+                .field(&vaerr)
+                .field(&path)
+                .finish(),
         }
     }
 }
@@ -0,0 +1,379 @@
+use std::marker::PhantomData;
+
+use serde::{
+    de::{DeserializeSeed, Error as _, MapAccess},
+    Deserialize, Deserializer,
+};
+
+use super::path::{self, Segment};
+
+/// Decides how the value of a field is read from the input and turned into the argument passed to
+/// the `FinalBuilder`. `P` is the tuple of arguments produced by the fields declared before it, `C`
+/// the context passed to `deserialize_with_context`.
+pub trait FieldDecoder<FT, P, C = ()> {
+    type State: Default;
+
+    /// Called for every occurrence of the field's key that is accepted by the duplicate policy
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<(), A::Error>;
+
+    /// Called for the field's element when the struct is read from a sequence, in which every
+    /// field is written as its plain value and collected fields as sequences. Returns whether the
+    /// element held a value.
+    fn decode_element<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<bool, A::Error> {
+        self.decode(state, map, ctx).map(|()| true)
+    }
+
+    /// Produces the argument once the whole input was read, `None` if the field is missing
+    fn finish(self, state: Self::State, prefix: &P) -> Option<FT>;
+}
+
+/// Reads the field's value with its `Deserialize` implementation
+pub struct ValueDecoder;
+
+impl<FT, P, C> FieldDecoder<FT, P, C> for ValueDecoder
+where
+    FT: for<'a> Deserialize<'a>,
+{
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        *state = Some(path::next_value(map)?);
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<FT> {
+        state
+    }
+}
+
+/// Reads the field's value when present, a missing field produces `None`
+pub struct OptionalDecoder;
+
+impl<FT, P, C> FieldDecoder<Option<FT>, P, C> for OptionalDecoder
+where
+    FT: for<'a> Deserialize<'a>,
+{
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        *state = Some(path::next_value(map)?);
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<Option<FT>> {
+        Some(state)
+    }
+}
+
+/// Produces a value without going through its `Deserialize` implementation, as the `deserialize`
+/// function of a `#[serde(with = "...")]` module does
+pub trait DeserializeWith<FT> {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FT, D::Error>;
+}
+
+struct WithSeed<W, FT>(PhantomData<fn() -> (W, FT)>);
+
+impl<'de, W: DeserializeWith<FT>, FT> DeserializeSeed<'de> for WithSeed<W, FT> {
+    type Value = FT;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<FT, D::Error> {
+        W::deserialize(deserializer)
+    }
+}
+
+/// Reads the field's value with `W`
+pub struct WithDecoder<W>(pub(crate) PhantomData<fn() -> W>);
+
+impl<FT, P, C, W: DeserializeWith<FT>> FieldDecoder<FT, P, C> for WithDecoder<W> {
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        *state = Some(path::next_value_seed(map, WithSeed::<W, FT>(PhantomData))?);
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<FT> {
+        state
+    }
+}
+
+/// Reads the field's value with `W` when present, a missing field produces `None`
+pub struct OptionalWithDecoder<W>(pub(crate) PhantomData<fn() -> W>);
+
+impl<FT, P, C, W: DeserializeWith<FT>> FieldDecoder<Option<FT>, P, C> for OptionalWithDecoder<W> {
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        *state = Some(path::next_value_seed(map, WithSeed::<W, FT>(PhantomData))?);
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<Option<FT>> {
+        Some(state)
+    }
+}
+
+/// Decoders of fields producing `None` when missing, which `required_if` and `required_unless`
+/// apply to
+pub trait OptionalFieldDecoder {}
+
+impl OptionalFieldDecoder for OptionalDecoder {}
+
+impl<W> OptionalFieldDecoder for OptionalWithDecoder<W> {}
+
+/// Optional field read with `D` that is required when `predicate`, called with the arguments of
+/// the fields declared before it, returns `required_when`
+pub struct ConditionalDecoder<F, D = OptionalDecoder> {
+    pub(crate) decoder: D,
+    pub(crate) predicate: F,
+    pub(crate) required_when: bool,
+}
+
+impl<FT, P, C, F, D> FieldDecoder<Option<FT>, P, C> for ConditionalDecoder<F, D>
+where
+    D: FieldDecoder<Option<FT>, P, C, State = Option<FT>>,
+    F: FnOnce(&P) -> bool,
+{
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        self.decoder.decode(state, map, ctx)
+    }
+
+    fn decode_element<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<bool, A::Error> {
+        self.decoder.decode_element(state, map, ctx)
+    }
+
+    fn finish(self, state: Self::State, prefix: &P) -> Option<Option<FT>> {
+        match state {
+            None if (self.predicate)(prefix) == self.required_when => None,
+            state => self.decoder.finish(state, prefix),
+        }
+    }
+}
+
+/// Collects every occurrence of the field into a `Vec`, a missing field produces an empty `Vec`
+pub struct CollectDecoder;
+
+impl<FT, P, C> FieldDecoder<Vec<FT>, P, C> for CollectDecoder
+where
+    FT: for<'a> Deserialize<'a>,
+{
+    type State = Vec<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        let mark = path::mark();
+        let value = path::next_value(map)
+            .inspect_err(|_| path::insert(mark, Segment::Index(state.len())))?;
+        state.push(value);
+        Ok(())
+    }
+
+    fn decode_element<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<bool, A::Error> {
+        *state = path::next_value(map)?;
+        Ok(!state.is_empty())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<Vec<FT>> {
+        Some(state)
+    }
+}
+
+/// Produces the value with a closure without reading anything from the input
+pub struct SkipDecoder<F>(pub(crate) F);
+
+impl<FT, P, C, F> FieldDecoder<FT, P, C> for SkipDecoder<F>
+where
+    F: FnOnce() -> FT,
+{
+    type State = ();
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        _state: &mut Self::State,
+        _map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        Ok(())
+    }
+
+    fn finish(self, _state: Self::State, _prefix: &P) -> Option<FT> {
+        Some((self.0)())
+    }
+}
+
+/// Computes the value from the arguments of the fields declared before it
+pub struct ComputedDecoder<F>(pub(crate) F);
+
+impl<FT, P, C, F> FieldDecoder<FT, P, C> for ComputedDecoder<F>
+where
+    F: FnOnce(&P) -> FT,
+{
+    type State = ();
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        _state: &mut Self::State,
+        _map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        Ok(())
+    }
+
+    fn finish(self, _state: Self::State, prefix: &P) -> Option<FT> {
+        Some((self.0)(prefix))
+    }
+}
+
+/// Tuple of field decoders producing `ARGS` for the `FinalBuilder`
+pub trait FieldDecoders<ARGS, C = ()> {
+    type States;
+
+    fn states(&self) -> Self::States;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        states: &mut Self::States,
+        index: usize,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<(), A::Error>;
+
+    /// Reads the element of the field at `index` of a struct written as a sequence, see
+    /// `FieldDecoder::decode_element`
+    fn decode_element<'de, A: MapAccess<'de>>(
+        &mut self,
+        states: &mut Self::States,
+        index: usize,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<bool, A::Error>;
+
+    /// Produces the arguments, or the index of the first missing field
+    fn finish(self, states: Self::States) -> Result<ARGS, usize>;
+}
+
+macro_rules! field_decoders_impl {
+    ($($len:expr => ($($n:tt $name:ident $dtype:ident $dname:ident $sname:ident $fname:ident [$($pname:ident $pfname:ident)*]),+))+) => {
+        $(
+            impl<C, $($name, $dtype,)+> FieldDecoders<($($name,)+), C> for ($($dtype,)+)
+            where
+                $($dtype: FieldDecoder<$name, ($($pname,)*), C>,)+
+            {
+                type States = ($($dtype::State,)+);
+
+                fn states(&self) -> Self::States {
+                    ($(<$dtype::State>::default(),)+)
+                }
+
+                fn decode<'de, A: MapAccess<'de>>(
+                    &mut self,
+                    states: &mut Self::States,
+                    index: usize,
+                    map: &mut A,
+                    ctx: &mut C,
+                ) -> Result<(), A::Error> {
+                    match index {
+                        $($n => self.$n.decode(&mut states.$n, map, ctx),)+
+                        // indexes come from the field table, which has exactly one entry per decoder
+                        _ => Err(A::Error::custom(format_args!("no field at index {index}"))),
+                    }
+                }
+
+                fn decode_element<'de, A: MapAccess<'de>>(
+                    &mut self,
+                    states: &mut Self::States,
+                    index: usize,
+                    map: &mut A,
+                    ctx: &mut C,
+                ) -> Result<bool, A::Error> {
+                    match index {
+                        $($n => self.$n.decode_element(&mut states.$n, map, ctx),)+
+                        _ => Err(A::Error::custom(format_args!("no field at index {index}"))),
+                    }
+                }
+
+                #[allow(clippy::let_unit_value, clippy::unused_unit)]
+                fn finish(self, states: Self::States) -> Result<($($name,)+), usize> {
+                    let ($($dname,)+) = self;
+                    let ($($sname,)+) = states;
+                    $(
+                        let prefix = ($($pfname,)*);
+                        let $fname = $dname.finish($sname, &prefix).ok_or($n as usize)?;
+                        let ($($pfname,)*) = prefix;
+                    )+
+                    Ok(($($fname,)+))
+                }
+            }
+        )+
+    }
+}
+
+field_decoders_impl! {
+    1 => (0 T0 D0 d0 s0 f0 [])
+    2 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0])
+    3 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1])
+    4 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2])
+    5 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3])
+    6 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4])
+    7 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5])
+    8 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6])
+    9 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7])
+    10 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8])
+    11 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8], 10 T10 D10 d10 s10 f10 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9])
+    12 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8], 10 T10 D10 d10 s10 f10 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9], 11 T11 D11 d11 s11 f11 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10])
+    13 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8], 10 T10 D10 d10 s10 f10 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9], 11 T11 D11 d11 s11 f11 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10], 12 T12 D12 d12 s12 f12 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11])
+    14 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8], 10 T10 D10 d10 s10 f10 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9], 11 T11 D11 d11 s11 f11 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10], 12 T12 D12 d12 s12 f12 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11], 13 T13 D13 d13 s13 f13 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11 T12 f12])
+    15 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8], 10 T10 D10 d10 s10 f10 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9], 11 T11 D11 d11 s11 f11 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10], 12 T12 D12 d12 s12 f12 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11], 13 T13 D13 d13 s13 f13 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11 T12 f12], 14 T14 D14 d14 s14 f14 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11 T12 f12 T13 f13])
+    16 => (0 T0 D0 d0 s0 f0 [], 1 T1 D1 d1 s1 f1 [T0 f0], 2 T2 D2 d2 s2 f2 [T0 f0 T1 f1], 3 T3 D3 d3 s3 f3 [T0 f0 T1 f1 T2 f2], 4 T4 D4 d4 s4 f4 [T0 f0 T1 f1 T2 f2 T3 f3], 5 T5 D5 d5 s5 f5 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4], 6 T6 D6 d6 s6 f6 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5], 7 T7 D7 d7 s7 f7 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6], 8 T8 D8 d8 s8 f8 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7], 9 T9 D9 d9 s9 f9 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8], 10 T10 D10 d10 s10 f10 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9], 11 T11 D11 d11 s11 f11 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10], 12 T12 D12 d12 s12 f12 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11], 13 T13 D13 d13 s13 f13 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11 T12 f12], 14 T14 D14 d14 s14 f14 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11 T12 f12 T13 f13], 15 T15 D15 d15 s15 f15 [T0 f0 T1 f1 T2 f2 T3 f3 T4 f4 T5 f5 T6 f6 T7 f7 T8 f8 T9 f9 T10 f10 T11 f11 T12 f12 T13 f13 T14 f14])
+}
@@ -0,0 +1,73 @@
+use super::field_spec::FieldSpec;
+
+/// How many fields of a group can be present, relative to one
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum GroupKind {
+    Exactly,
+    AtMost,
+    AtLeast,
+}
+
+/// Constraint on how many fields of a set can be present in the input
+#[derive(Debug, Clone)]
+pub(crate) struct FieldGroup {
+    pub(crate) kind: GroupKind,
+    pub(crate) members: Vec<usize>,
+}
+
+impl GroupKind {
+    /// Builder method adding a group of this kind
+    pub(crate) fn method(self) -> &'static str {
+        match self {
+            Self::Exactly => "exactly_one_of",
+            Self::AtMost => "at_most_one_of",
+            Self::AtLeast => "at_least_one_of",
+        }
+    }
+
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Self::Exactly => "exactly one of",
+            Self::AtMost => "at most one of",
+            Self::AtLeast => "at least one of",
+        }
+    }
+}
+
+impl FieldGroup {
+    /// Checks the group against the fields found in the input, describing the violation if any
+    pub(crate) fn check(&self, seen: &[bool], fields: &[FieldSpec]) -> Result<(), String> {
+        let present: Vec<usize> = self
+            .members
+            .iter()
+            .copied()
+            .filter(|&index| seen[index])
+            .collect();
+        let expected = self.kind.description();
+        let violated = match self.kind {
+            GroupKind::Exactly => present.len() != 1,
+            GroupKind::AtMost => present.len() > 1,
+            GroupKind::AtLeast => present.is_empty(),
+        };
+        if !violated {
+            return Ok(());
+        }
+        let members = list(&self.members, fields);
+        if present.is_empty() {
+            Err(format!("missing field, expected {expected} {members}"))
+        } else {
+            Err(format!(
+                "conflicting fields {}, expected {expected} {members}",
+                list(&present, fields)
+            ))
+        }
+    }
+}
+
+fn list(indices: &[usize], fields: &[FieldSpec]) -> String {
+    indices
+        .iter()
+        .map(|&index| format!("`{}`", fields[index].label()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
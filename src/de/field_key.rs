@@ -0,0 +1,262 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use serde::de::{DeserializeSeed, Deserializer, Error, Visitor};
+
+use super::{
+    field_spec::FieldSpec,
+    key_matching::{KeyMatching, Normalized},
+};
+use crate::case::Case;
+
+/// Lookup table resolving keys to field indices, built once the builder is finalized
+pub(crate) struct FieldTable {
+    /// Field names with `rename_all` applied, normalised by the key matching strategy
+    index: HashMap<NormalizedName, usize>,
+    ids: HashMap<u64, usize>,
+    /// Keys accepted by the table, kept only when unknown keys are rejected
+    expected: Option<Box<[String]>>,
+    /// Two fields matching the same keys, which would make one of them unreachable
+    conflict: Option<String>,
+    /// Labels of the fields given to `deserialize_struct`, interned so that building the same
+    /// builder again doesn't leak them again
+    #[cfg(feature = "leaking")]
+    static_names: &'static [&'static str],
+}
+
+impl FieldTable {
+    pub(crate) fn new(
+        fields: &[FieldSpec],
+        rename_all: Option<Case>,
+        key_matching: &KeyMatching,
+        deny_unknown_fields: bool,
+    ) -> Self {
+        let names: Vec<Option<String>> = fields
+            .iter()
+            .map(|field| {
+                let name = field.name.as_deref()?;
+                Some(match rename_all {
+                    Some(case) => case.apply(name),
+                    None => name.to_owned(),
+                })
+            })
+            .collect();
+        let mut conflict = None;
+        let mut index = HashMap::new();
+        for (i, name) in names.iter().enumerate() {
+            let Some(name) = name else { continue };
+            let normalized = NormalizedName(key_matching.normalize(name).into());
+            if let Some(previous) = index.insert(normalized, i) {
+                conflict.get_or_insert_with(|| {
+                    format!(
+                        "fields `{}` and `{name}` match the same keys",
+                        names[previous].as_deref().unwrap_or_default()
+                    )
+                });
+            }
+        }
+        let mut ids = HashMap::new();
+        for (i, field) in fields.iter().enumerate() {
+            let Some(id) = field.id else { continue };
+            if let Some(previous) = ids.insert(id, i) {
+                conflict.get_or_insert_with(|| {
+                    format!(
+                        "fields `{}` and `{}` have the same id {id}",
+                        fields[previous].label(),
+                        field.label()
+                    )
+                });
+            }
+        }
+        #[cfg(feature = "leaking")]
+        let static_names = crate::intern::intern_names(
+            names
+                .iter()
+                .zip(fields)
+                .map(|(name, field)| name.clone().unwrap_or_else(|| field.label())),
+        );
+        let expected = deny_unknown_fields.then(|| {
+            names
+                .into_iter()
+                .zip(fields)
+                .filter_map(|(name, field)| name.or_else(|| Some(field.id?.to_string())))
+                .collect()
+        });
+        Self {
+            index,
+            ids,
+            expected,
+            conflict,
+            #[cfg(feature = "leaking")]
+            static_names,
+        }
+    }
+
+    #[cfg(feature = "leaking")]
+    pub(crate) fn static_names(&self) -> &'static [&'static str] {
+        self.static_names
+    }
+
+    /// Error for fields that can't be told apart, reported by `deserialize`
+    pub(crate) fn conflict(&self) -> Option<&str> {
+        self.conflict.as_deref()
+    }
+
+    /// Finds the field of `key`, normalised with the strategy the table was built with. Only
+    /// `KeyMatching::Custom` builds a normalised string.
+    pub(crate) fn resolve(&self, key: &str, key_matching: &KeyMatching) -> Option<usize> {
+        match key_matching {
+            KeyMatching::Custom(normalize) => {
+                let key = normalize(key);
+                self.get(Normalized::verbatim(&key))
+            }
+            key_matching => self.get(key_matching.normalized(key)),
+        }
+    }
+
+    fn get(&self, key: Normalized) -> Option<usize> {
+        self.index.get(&key as &dyn AsNormalized).copied()
+    }
+
+    pub(crate) fn resolve_id(&self, id: u64) -> Option<usize> {
+        self.ids.get(&id).copied()
+    }
+
+    fn unknown<E: Error>(&self, key: &dyn fmt::Display) -> Result<Option<usize>, E> {
+        let Some(expected) = &self.expected else {
+            return Ok(None);
+        };
+        Err(match &expected[..] {
+            [] => E::custom(format_args!("unknown field `{key}`, there are no fields")),
+            [name] => E::custom(format_args!("unknown field `{key}`, expected `{name}`")),
+            [first, second] => E::custom(format_args!(
+                "unknown field `{key}`, expected `{first}` or `{second}`"
+            )),
+            names => E::custom(format_args!(
+                "unknown field `{key}`, expected one of {}",
+                names
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        })
+    }
+}
+
+/// Normalised field name, hashed and compared like the `Normalized` keys looked up in the table
+struct NormalizedName(Box<str>);
+
+/// Common view of stored names and looked up keys, so that keys are found without normalising
+/// them into a new string
+trait AsNormalized {
+    fn as_normalized(&self) -> Normalized<'_>;
+}
+
+impl AsNormalized for NormalizedName {
+    fn as_normalized(&self) -> Normalized<'_> {
+        Normalized::verbatim(&self.0)
+    }
+}
+
+impl AsNormalized for Normalized<'_> {
+    fn as_normalized(&self) -> Normalized<'_> {
+        *self
+    }
+}
+
+impl Hash for dyn AsNormalized + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.as_normalized().chars() {
+            c.hash(state);
+        }
+    }
+}
+
+impl PartialEq for dyn AsNormalized + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_normalized()
+            .chars()
+            .eq(other.as_normalized().chars())
+    }
+}
+
+impl Eq for dyn AsNormalized + '_ {}
+
+impl Hash for NormalizedName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self as &dyn AsNormalized).hash(state);
+    }
+}
+
+impl PartialEq for NormalizedName {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for NormalizedName {}
+
+impl<'a> Borrow<dyn AsNormalized + 'a> for NormalizedName {
+    fn borrow(&self) -> &(dyn AsNormalized + 'a) {
+        self
+    }
+}
+
+/// Deserializes a map key as a field identifier, producing the index of the matched field or
+/// `None` for unknown keys
+pub(crate) struct FieldKeySeed<'a> {
+    pub(crate) table: &'a FieldTable,
+    pub(crate) key_matching: &'a KeyMatching,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for FieldKeySeed<'a> {
+    type Value = Option<usize>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for FieldKeySeed<'a> {
+    type Value = Option<usize>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("field identifier")
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        match self.table.resolve_id(v) {
+            Some(index) => Ok(Some(index)),
+            None => self.table.unknown(&v),
+        }
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        match u64::try_from(v).ok().and_then(|v| self.table.resolve_id(v)) {
+            Some(index) => Ok(Some(index)),
+            None => self.table.unknown(&v),
+        }
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        match self.table.resolve(v, self.key_matching) {
+            Some(index) => Ok(Some(index)),
+            None => self.table.unknown(&v),
+        }
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match std::str::from_utf8(v)
+            .ok()
+            .and_then(|v| self.table.resolve(v, self.key_matching))
+        {
+            Some(index) => Ok(Some(index)),
+            None => self.table.unknown(&String::from_utf8_lossy(v)),
+        }
+    }
+}
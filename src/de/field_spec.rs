@@ -0,0 +1,135 @@
+/// What to do when a key of a field appears more than once in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateFields {
+    #[default]
+    Error,
+    /// Keep the first occurrence, ignore the following ones
+    First,
+    /// Every occurrence overwrites the previous one. Nested fields can't use it, as their
+    /// deserializer is consumed by the first occurrence, which `deserialize` reports as an error.
+    Last,
+}
+
+#[cfg(not(feature = "leaking"))]
+pub(crate) const PLACEHOLDER_NAMES: [&str; 16] = [
+    "field0", "field1", "field2", "field3", "field4", "field5", "field6", "field7", "field8",
+    "field9", "field10", "field11", "field12", "field13", "field14", "field15",
+];
+
+/// How an occurrence of a field's key is handled
+pub(crate) enum Occurrence {
+    Decode,
+    Ignore,
+    Duplicate,
+}
+
+/// Runtime description of a registered field
+#[derive(Debug, Clone)]
+pub(crate) struct FieldSpec {
+    pub(crate) name: Option<String>,
+    pub(crate) id: Option<u64>,
+    pub(crate) duplicates: Option<DuplicateFields>,
+    /// Every occurrence is passed to the decoder, regardless of the duplicate policy
+    pub(crate) repeatable: bool,
+    /// Read with a nested deserializer, which can only read one occurrence
+    pub(crate) nested: bool,
+    /// Condition under which an optional field is required, reported when it is missing
+    pub(crate) requirement: Option<String>,
+}
+
+impl FieldSpec {
+    pub(crate) fn named(name: String) -> Self {
+        Self {
+            name: Some(name),
+            id: None,
+            duplicates: None,
+            repeatable: false,
+            nested: false,
+            requirement: None,
+        }
+    }
+
+    pub(crate) fn with_id(id: u64) -> Self {
+        Self {
+            name: None,
+            id: Some(id),
+            duplicates: None,
+            repeatable: false,
+            nested: false,
+            requirement: None,
+        }
+    }
+
+    /// Field that has no key and is never read from the input
+    pub(crate) fn skipped() -> Self {
+        Self {
+            name: None,
+            id: None,
+            duplicates: None,
+            repeatable: false,
+            nested: false,
+            requirement: None,
+        }
+    }
+
+    pub(crate) fn repeatable(self) -> Self {
+        Self {
+            repeatable: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn nested(self) -> Self {
+        Self {
+            nested: true,
+            ..self
+        }
+    }
+
+    /// Error for a duplicate policy the field can't follow, reported by `deserialize`
+    pub(crate) fn policy_error(&self, duplicate_fields: DuplicateFields) -> Option<String> {
+        let last = self.duplicates.unwrap_or(duplicate_fields) == DuplicateFields::Last;
+        (self.nested && last).then(|| {
+            format!(
+                "nested field `{}` can't use `DuplicateFields::Last`, its deserializer reads only one occurrence",
+                self.label()
+            )
+        })
+    }
+
+    /// Whether the field is read from the input, false for skipped and computed fields
+    pub(crate) fn is_read(&self) -> bool {
+        self.name.is_some() || self.id.is_some()
+    }
+
+    /// Applies the duplicate policy to an occurrence of the field's key
+    pub(crate) fn occurrence(&self, seen: bool, duplicate_fields: DuplicateFields) -> Occurrence {
+        if !seen || self.repeatable {
+            return Occurrence::Decode;
+        }
+        match self.duplicates.unwrap_or(duplicate_fields) {
+            DuplicateFields::Error => Occurrence::Duplicate,
+            DuplicateFields::First => Occurrence::Ignore,
+            DuplicateFields::Last => Occurrence::Decode,
+        }
+    }
+
+    /// Name used to refer to the field in errors
+    pub(crate) fn label(&self) -> String {
+        match (&self.name, self.id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(id)) => id.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Name for serde errors, which require `&'static str`. Without `leaking`, fields past the
+    /// placeholders, which only dynamic structs can have, are all called `field`.
+    #[cfg_attr(feature = "leaking", allow(unused_variables))]
+    pub(crate) fn static_name(&self, index: usize) -> &'static str {
+        #[cfg(not(feature = "leaking"))]
+        return PLACEHOLDER_NAMES.get(index).copied().unwrap_or("field");
+        #[cfg(feature = "leaking")]
+        return crate::intern::intern(&self.label());
+    }
+}
@@ -1,181 +1,250 @@
-use std::{collections::HashMap, marker::PhantomData};
-
-use serde::{de::Visitor, Deserialize};
-
-use super::FinalBuilder;
-
-pub(crate) struct FieldVisitor<T, FBARGS, FB, const FN: usize> {
-    field_names: [String; FN],
-    field_index: HashMap<String, usize>,
-    final_builder: FB,
-    target_phantom: PhantomData<T>,
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{DeserializeSeed, Error as _, Expected, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+use super::{
+    field_group::FieldGroup,
+    field_key::{FieldKeySeed, FieldTable},
+    field_spec::{FieldSpec, Occurrence},
+    path::{self, Segment},
+    DuplicateFields, FieldDecoders, KeyMatching,
+};
+
+pub(crate) struct FieldVisitor<'c, FBARGS, FDS, C, const FN: usize> {
+    fields: [FieldSpec; FN],
+    field_table: FieldTable,
+    key_matching: KeyMatching,
+    decoders: FDS,
+    ctx: &'c mut C,
+    duplicate_fields: DuplicateFields,
+    groups: Vec<FieldGroup>,
+    name: &'static str,
+    expecting: Option<String>,
     fields_phantom: PhantomData<FBARGS>,
 }
 
-impl<T, T0, FB> FieldVisitor<T, T0, FB, 1>
-where
-    T0: for<'a> Deserialize<'a>,
-    FB: FinalBuilder<T, T0>,
-{
-    pub(crate) fn new(final_builder: FB, field_names: [String; 1]) -> Self {
-        let field_index = field_names
-            .iter()
-            .enumerate()
-            .map(|(i, n)| (n.clone(), i))
-            .collect();
+impl<'c, FBARGS, FDS, C, const FN: usize> FieldVisitor<'c, FBARGS, FDS, C, FN> {
+    pub(crate) fn new(
+        decoders: FDS,
+        ctx: &'c mut C,
+        fields: [FieldSpec; FN],
+        field_table: FieldTable,
+        key_matching: KeyMatching,
+        duplicate_fields: DuplicateFields,
+        groups: Vec<FieldGroup>,
+    ) -> Self {
         Self {
-            field_names,
-            field_index,
-            final_builder,
-            target_phantom: PhantomData,
+            fields,
+            field_table,
+            key_matching,
+            decoders,
+            ctx,
+            duplicate_fields,
+            groups,
+            name: "",
+            expecting: None,
             fields_phantom: PhantomData,
         }
     }
+
+    /// Struct name and custom description used by `Visitor::expecting`
+    pub(crate) fn expecting(mut self, name: &'static str, expecting: Option<String>) -> Self {
+        self.name = name;
+        self.expecting = expecting;
+        self
+    }
 }
 
-impl<'de, T, T0, FB> Visitor<'de> for FieldVisitor<T, T0, FB, 1>
+impl<'de, 'c, FBARGS, FDS, C, const FN: usize> Visitor<'de> for FieldVisitor<'c, FBARGS, FDS, C, FN>
 where
-    T0: for<'a> Deserialize<'a>,
-    FB: FinalBuilder<T, T0>,
+    FDS: FieldDecoders<FBARGS, C>,
 {
-    type Value = T;
+    type Value = FBARGS;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("struct")
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.expecting {
+            Some(expecting) => formatter.write_str(expecting),
+            None => write!(formatter, "struct {}", self.name),
+        }
     }
 
     fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
     where
-        A: serde::de::MapAccess<'de>,
+        A: MapAccess<'de>,
     {
-        let mut field0: Option<T0> = None;
-
-        while let Some(key) = map.next_key()? {
-            if self.field_names.contains(&key) {
-                match self.field_index.get(&key) {
-                    Some(0) => {
-                        if field0.is_some() {
-                            #[cfg(not(feature = "leaking"))]
-                            return Err(serde::de::Error::duplicate_field("field0"));
-                            #[cfg(feature = "leaking")]
-                            return Err(serde::de::Error::duplicate_field(&*Box::leak(
-                                self.field_names[0].clone().into_boxed_str(),
-                            )));
-                        }
-                        field0 = Some(map.next_value()?);
-                    }
-                    // field_index was constructed based on field_names array, so it can't contain
-                    // indexes larger than max index of field_names, and cannot contain keys that
-                    // are not in field_names
-                    _ => unreachable!(),
+        let FieldVisitor {
+            fields,
+            field_table,
+            key_matching,
+            mut decoders,
+            ctx,
+            duplicate_fields,
+            groups,
+            name: _,
+            expecting: _,
+            fields_phantom: _,
+        } = self;
+        let mut states = decoders.states();
+        let mut seen = [false; FN];
+        let path_mark = path::mark();
+
+        while let Some(index) = map.next_key_seed(FieldKeySeed {
+            table: &field_table,
+            key_matching: &key_matching,
+        })? {
+            let Some(index) = index else {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+            let field = &fields[index];
+            match field.occurrence(seen[index], duplicate_fields) {
+                Occurrence::Decode => {}
+                Occurrence::Ignore => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+                Occurrence::Duplicate => {
+                    return Err(A::Error::duplicate_field(field.static_name(index)))
                 }
             }
+            seen[index] = true;
+            path::reset(path_mark);
+            if let Err(e) = decoders.decode(&mut states, index, &mut map, ctx) {
+                path::insert(path_mark, Segment::Field(field.label()));
+                return Err(e);
+            }
         }
 
-        #[cfg(not(feature = "leaking"))]
-        let field0 = field0.ok_or_else(|| serde::de::Error::missing_field("field0"))?;
-        #[cfg(feature = "leaking")]
-        let field0 = field0.ok_or_else(|| {
-            serde::de::Error::missing_field(&*Box::leak(
-                self.field_names[0].clone().into_boxed_str(),
-            ))
-        })?;
-
-        Ok(self.final_builder.assemble(field0).unwrap())
+        finish(decoders, states, &fields, &seen, &groups, |index| {
+            missing_field(&fields, index)
+        })
     }
-}
 
-macro_rules! field_visitor_impl {
-    ($($len:expr => ($($n:tt $name:ident $fname:ident),+))+) => {
-        $(
-            impl<T, $($name,)+ FB> FieldVisitor<T, ($($name),+), FB, $len>
-            where
-                $($name: for<'a> Deserialize<'a>,)+
-                FB: FinalBuilder<T, ($($name,)+)>,
-            {
-                pub(crate) fn new(final_builder: FB, field_names: [String; $len]) -> Self {
-                    let field_index = field_names
-                        .iter()
-                        .enumerate()
-                        .map(|(i, n)| (n.clone(), i))
-                        .collect();
-                    Self {
-                        field_names,
-                        field_index,
-                        final_builder,
-                        target_phantom: PhantomData::default(),
-                        fields_phantom: PhantomData::default(),
-                    }
+    /// Reads structs written as sequences by formats that aren't self-describing, with one element
+    /// per field read from the input, in the order the fields were added
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let FieldVisitor {
+            fields,
+            field_table: _,
+            key_matching: _,
+            mut decoders,
+            ctx,
+            duplicate_fields: _,
+            groups,
+            name,
+            expecting,
+            fields_phantom: _,
+        } = self;
+        let expected = ExpectedElements {
+            name,
+            expecting: expecting.as_deref(),
+            len: fields.iter().filter(|field| field.is_read()).count(),
+        };
+        let mut elements = SeqElements {
+            seq: &mut seq,
+            read: 0,
+            ended: false,
+            expected: &expected,
+        };
+        let mut states = decoders.states();
+        let mut seen = [false; FN];
+        let path_mark = path::mark();
+
+        for (index, field) in fields.iter().enumerate() {
+            if !field.is_read() {
+                continue;
+            }
+            path::reset(path_mark);
+            match decoders.decode_element(&mut states, index, &mut elements, ctx) {
+                Ok(present) => seen[index] = present,
+                // like serde derive, the fields after the end of a shorter sequence are missing,
+                // which only fails if one of them is required
+                Err(_) if elements.ended => break,
+                Err(e) => {
+                    path::insert(path_mark, Segment::Field(field.label()));
+                    return Err(e);
                 }
             }
+        }
+
+        let read = elements.read;
+        finish(decoders, states, &fields, &seen, &groups, |_| {
+            A::Error::invalid_length(read, &expected)
+        })
+    }
+}
 
-            impl<'de, T, $($name,)+ FB> Visitor<'de> for FieldVisitor<T, ($($name,)+), FB, $len>
-            where
-                $($name: for<'a> Deserialize<'a>,)+
-                FB: FinalBuilder<T, ($($name),+)>,
-            {
-                type Value = T;
+/// Checks the groups and produces the arguments once every key or element was read
+fn finish<FBARGS, FDS: FieldDecoders<FBARGS, C>, C, E: serde::de::Error>(
+    decoders: FDS,
+    states: FDS::States,
+    fields: &[FieldSpec],
+    seen: &[bool],
+    groups: &[FieldGroup],
+    missing: impl FnOnce(usize) -> E,
+) -> Result<FBARGS, E> {
+    for group in groups {
+        group.check(seen, fields).map_err(E::custom)?;
+    }
 
-                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                    formatter.write_str("struct")
-                }
+    decoders.finish(states).map_err(missing)
+}
 
-                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-                where
-                    A: serde::de::MapAccess<'de>
-                {
-                    $(let mut $fname: Option<$name> = None;)+
-
-                    while let Some(key) = map.next_key()? {
-                        if self.field_names.contains(&key) {
-                            match self.field_index.get(&key) {
-                                $(
-                                Some($n) => {
-                                    if $fname.is_some() {
-                                        #[cfg(not(feature = "leaking"))]
-                                        return Err(serde::de::Error::duplicate_field(stringify!($fname)));
-                                        #[cfg(feature = "leaking")]
-                                        return Err(serde::de::Error::duplicate_field(&*Box::leak(key.clone().into_boxed_str())));
-                                    }
-                                    $fname = Some(map.next_value()?);
-                                },
-                                )+
-                                // field_index was constructed based on field_names array, so it can't contain
-                                // indexes larger than max index of field_names, and cannot contain keys that
-                                // are not in field_names
-                                _ => unreachable!(),
-                            }
-                        }
-                    }
-
-                    $(
-                        #[cfg(not(feature = "leaking"))]
-                        let $fname = $fname.ok_or_else(|| serde::de::Error::missing_field(stringify!($fname)))?;
-                        #[cfg(feature = "leaking")]
-                        let $fname = $fname.ok_or_else(|| serde::de::Error::missing_field(&*Box::leak(self.field_names[($n as usize)].clone().into_boxed_str())))?;
-                    )+
-
-                    Ok(self.final_builder.assemble(($($fname),+)).unwrap())
-                }
-            }
-        )+
+/// Error for the field at `index` missing from a map
+fn missing_field<E: serde::de::Error>(fields: &[FieldSpec], index: usize) -> E {
+    let field = &fields[index];
+    match &field.requirement {
+        Some(requirement) => E::custom(format_args!(
+            "missing field `{}`, {requirement}",
+            field.label()
+        )),
+        None => E::missing_field(field.static_name(index)),
+    }
+}
+
+/// Hands the elements of a sequence to the decoders, which read values through `MapAccess`
+struct SeqElements<'a, A> {
+    seq: &'a mut A,
+    read: usize,
+    /// The sequence ran out of elements
+    ended: bool,
+    expected: &'a ExpectedElements<'a>,
+}
+
+impl<'de, A: SeqAccess<'de>> MapAccess<'de> for SeqElements<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        _seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        let Some(value) = self.seq.next_element_seed(seed)? else {
+            self.ended = true;
+            return Err(A::Error::invalid_length(self.read, self.expected));
+        };
+        self.read += 1;
+        Ok(value)
     }
 }
 
-field_visitor_impl! {
-    2 => (0 T0 field0, 1 T1 field1)
-    3 => (0 T0 field0, 1 T1 field1, 2 T2 field2)
-    4 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3)
-    5 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4)
-    6 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5)
-    7 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6)
-    8 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field7)
-    9 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8)
-    10 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9)
-    11 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9, 10 T10 field10)
-    12 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9, 10 T10 field10, 11 T11 field11)
-    13 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9, 10 T10 field10, 11 T11 field11, 12 T12 field12)
-    14 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9, 10 T10 field10, 11 T11 field11, 12 T12 field12, 13 T13 field13)
-    15 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9, 10 T10 field10, 11 T11 field11, 12 T12 field12, 13 T13 field13, 14 T14 field14)
-    16 => (0 T0 field0, 1 T1 field1, 2 T2 field2, 3 T3 field3, 4 T4 field4, 5 T5 field5, 6 T6 field6, 7 T7 field, 8 T8 field8, 9 T9 field9, 10 T10 field10, 11 T11 field11, 12 T12 field12, 13 T13 field13, 14 T14 field14, 15 T15 field15)
+struct ExpectedElements<'a> {
+    name: &'static str,
+    expecting: Option<&'a str>,
+    len: usize,
+}
+
+impl Expected for ExpectedElements<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.expecting {
+            Some(expecting) => formatter.write_str(expecting),
+            None => write!(formatter, "struct {} with {} elements", self.name, self.len),
+        }
+    }
 }
@@ -1,19 +1,25 @@
-pub trait FinalBuilder<T, ARGS> {
-    fn assemble(self, args: ARGS) -> Option<T>;
+/// Builds the value from the arguments produced by the fields. `()` doesn't implement it, so a
+/// builder can only deserialize once a final builder has been set.
+pub trait FinalBuilder<T, ARGS, C = ()> {
+    fn assemble(self, args: ARGS, ctx: &mut C) -> T;
 }
 
-impl<T, FBARGS> FinalBuilder<T, FBARGS> for () {
-    fn assemble(self, _args: FBARGS) -> Option<T> {
-        None
-    }
-}
+/// Final builder or validator receiving the context passed to `deserialize_with_context` as its
+/// first argument
+pub struct WithContext<F>(pub F);
 
 macro_rules! final_builder_impls {
     ($($len:expr => ($($n:tt $name:ident)+))+) => {
         $(
-            impl<T, FN: FnOnce($($name),+) -> T, $($name,)+> FinalBuilder<T, ($($name,)+)> for FN {
-                fn assemble(self, args: ($($name,)+)) -> Option<T> {
-                    Some(self($(args.$n,)+))
+            impl<T, C, FN: FnOnce($($name),+) -> T, $($name,)+> FinalBuilder<T, ($($name,)+), C> for FN {
+                fn assemble(self, args: ($($name,)+), _ctx: &mut C) -> T {
+                    self($(args.$n,)+)
+                }
+            }
+
+            impl<T, C, FN: FnOnce(&mut C, $($name),+) -> T, $($name,)+> FinalBuilder<T, ($($name,)+), C> for WithContext<FN> {
+                fn assemble(self, args: ($($name,)+), ctx: &mut C) -> T {
+                    (self.0)(ctx, $(args.$n,)+)
                 }
             }
         )+
@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+
+/// Strategy used to match keys found in the input against registered field names.
+///
+/// Both the key and the field names are normalised the same way before comparison, so two
+/// different keys normalising to the same field are reported as a duplicate field.
+///
+/// Field names normalising to the same string, like `user_name` and `username` with `Loose`, can't
+/// be told apart, `deserialize` rejects such builders with an error naming both fields.
+///
+/// Built-in strategies compare keys without allocating, `Custom` allocates a `String` per key.
+#[derive(Default)]
+pub enum KeyMatching {
+    #[default]
+    Exact,
+    /// Compare ignoring ASCII case, `userName` matches `UserName` and `username`
+    AsciiCaseInsensitive,
+    /// Compare ignoring `_` and `-`, `user_name` matches `username` and `user-name`
+    IgnoreSeparators,
+    /// Combination of `AsciiCaseInsensitive` and `IgnoreSeparators`
+    Loose,
+    Custom(Box<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl KeyMatching {
+    pub fn custom<F: Fn(&str) -> String + Send + Sync + 'static>(normalize: F) -> Self {
+        Self::Custom(Box::new(normalize))
+    }
+
+    pub(crate) fn normalize<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Exact => Cow::Borrowed(key),
+            Self::Custom(normalize) => Cow::Owned(normalize(key)),
+            _ => Cow::Owned(self.normalized(key).chars().collect()),
+        }
+    }
+
+    /// Normalised form of `key` for built-in strategies, computed lazily
+    pub(crate) fn normalized<'a>(&self, key: &'a str) -> Normalized<'a> {
+        let (ignore_case, ignore_separators) = match self {
+            Self::AsciiCaseInsensitive => (true, false),
+            Self::IgnoreSeparators => (false, true),
+            Self::Loose => (true, true),
+            Self::Exact | Self::Custom(_) => (false, false),
+        };
+        Normalized {
+            key,
+            ignore_case,
+            ignore_separators,
+        }
+    }
+}
+
+/// Key seen through the normalisation of a built-in strategy, compared without building the
+/// normalised string
+#[derive(Clone, Copy)]
+pub(crate) struct Normalized<'a> {
+    key: &'a str,
+    ignore_case: bool,
+    ignore_separators: bool,
+}
+
+impl<'a> Normalized<'a> {
+    /// Key that is already normalised
+    pub(crate) fn verbatim(key: &'a str) -> Self {
+        Self {
+            key,
+            ignore_case: false,
+            ignore_separators: false,
+        }
+    }
+
+    pub(crate) fn chars(self) -> impl Iterator<Item = char> + 'a {
+        let Self {
+            key,
+            ignore_case,
+            ignore_separators,
+        } = self;
+        key.chars()
+            .filter(move |c| !(ignore_separators && matches!(c, '_' | '-')))
+            .map(move |c| {
+                if ignore_case {
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                }
+            })
+    }
+}
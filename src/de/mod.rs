@@ -1,31 +1,124 @@
+mod content;
+mod describe;
+mod dynamic;
 pub mod error;
+mod field_decoder;
+mod field_group;
+mod field_key;
+mod field_spec;
 mod field_visitor;
 mod final_builder;
+mod key_matching;
+mod overlay;
+mod path;
+#[cfg(feature = "schema")]
+mod schema;
+mod seed;
+mod type_deserializer;
 mod validator;
+pub mod validators;
+mod versioned;
 
+use crate::{case::Case, short_type_name, NonEmpty};
+pub use describe::*;
+pub use dynamic::*;
 use error::Error;
+pub use field_decoder::*;
+use field_group::*;
+use field_key::FieldTable;
+pub use field_spec::DuplicateFields;
+use field_spec::*;
 use field_visitor::*;
 pub use final_builder::*;
+pub use key_matching::*;
+pub use overlay::*;
+use path::PathScope;
+pub use path::{Path, Segment};
+#[cfg(feature = "schema")]
+pub use schema::*;
+pub use seed::*;
+pub use type_deserializer::*;
 pub use validator::*;
+pub use versioned::*;
 
 use std::marker::PhantomData;
 
-use serde::{Deserialize, Deserializer};
+use serde::{de::Error as _, Deserializer};
 
 use concat_arrays::concat_arrays;
 
 pub struct StructDeserializer<
     T,
     FBARGS = (),
-    FB: FinalBuilder<T, FBARGS> = (),
-    V: Validator<T> = (),
+    FDS = (),
+    FB = (),
+    V: Validator<T, C> = (),
+    C = (),
     const FN: usize = 0,
 > {
     target_phantom: PhantomData<T>,
     fb_args_phantom: PhantomData<FBARGS>,
-    final_builder: Option<FB>,
-    validator: Option<V>,
-    field_names: [String; FN],
+    context_phantom: PhantomData<fn(&mut C)>,
+    decoders: FDS,
+    final_builder: FB,
+    validator: V,
+    fields: [FieldSpec; FN],
+    options: Options,
+}
+
+#[derive(Default)]
+pub(crate) struct Options {
+    pub(crate) name: Option<&'static str>,
+    pub(crate) expecting: Option<String>,
+    pub(crate) rename_all: Option<Case>,
+    pub(crate) key_matching: KeyMatching,
+    pub(crate) duplicate_fields: DuplicateFields,
+    pub(crate) deny_unknown_fields: bool,
+    pub(crate) groups: Vec<FieldGroup>,
+    /// Mistakes in the builder's configuration, reported by `deserialize` instead of panicking
+    pub(crate) errors: Vec<String>,
+    /// Built when the final builder is set, then rebuilt by the changes to the fields' keys
+    pub(crate) field_table: Option<FieldTable>,
+}
+
+impl Options {
+    pub(crate) fn build_field_table(&mut self, fields: &[FieldSpec]) {
+        self.field_table = Some(FieldTable::new(
+            fields,
+            self.rename_all,
+            &self.key_matching,
+            self.deny_unknown_fields,
+        ));
+    }
+
+    /// Keeps the field table in sync with a change to the fields' keys, once it was built
+    pub(crate) fn refresh_field_table(&mut self, fields: &[FieldSpec]) {
+        if self.field_table.is_some() {
+            self.build_field_table(fields);
+        }
+    }
+
+    /// Adds a group of the named fields, or records an error for `deserialize` to report when a
+    /// name isn't one of the added fields
+    pub(crate) fn add_group(&mut self, kind: GroupKind, names: &[&str], fields: &[FieldSpec]) {
+        let mut members = Vec::with_capacity(names.len());
+        for &name in names {
+            match fields
+                .iter()
+                .position(|field| field.name.as_deref() == Some(name))
+            {
+                Some(index) => members.push(index),
+                None => {
+                    self.errors.push(format!(
+                        "`{name}` in `{}` isn't the name of an added field",
+                        kind.method()
+                    ));
+                    return;
+                }
+            }
+        }
+        self.groups.push(FieldGroup { kind, members });
+    }
 }
 
 impl<T> Default for StructDeserializer<T> {
@@ -33,9 +126,12 @@ impl<T> Default for StructDeserializer<T> {
         Self {
             target_phantom: PhantomData,
             fb_args_phantom: PhantomData,
-            final_builder: None,
-            validator: None,
-            field_names: [],
+            context_phantom: PhantomData,
+            decoders: (),
+            final_builder: (),
+            validator: (),
+            fields: [],
+            options: Options::default(),
         }
     }
 }
@@ -44,174 +140,679 @@ impl<T> StructDeserializer<T> {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Sets the type of the context passed to `deserialize_with_context`
+    pub fn context<C>(self) -> StructDeserializer<T, (), (), (), (), C, 0> {
+        let StructDeserializer {
+            target_phantom,
+            fb_args_phantom,
+            context_phantom: _,
+            decoders,
+            final_builder: _,
+            validator: _,
+            fields,
+            options,
+        } = self;
+        StructDeserializer {
+            target_phantom,
+            fb_args_phantom,
+            context_phantom: PhantomData,
+            decoders,
+            final_builder: (),
+            validator: (),
+            fields,
+            options,
+        }
+    }
+}
+
+impl<T, C, FBARGS, FDS, FB, V: Validator<T, C>, const FN: usize>
+    StructDeserializer<T, FBARGS, FDS, FB, V, C, FN>
+{
+    /// Adds a validator running after the ones already set, see `And`
+    pub fn and_validator<W: Validator<T, C>>(
+        self,
+        validator: W,
+    ) -> StructDeserializer<T, FBARGS, FDS, FB, And<V, W>, C, FN> {
+        let StructDeserializer {
+            target_phantom,
+            fb_args_phantom,
+            context_phantom,
+            decoders,
+            final_builder,
+            validator: previous,
+            fields,
+            options,
+        } = self;
+        StructDeserializer {
+            target_phantom,
+            fb_args_phantom,
+            context_phantom,
+            decoders,
+            final_builder,
+            validator: And(previous, validator),
+            fields,
+            options,
+        }
+    }
+
+    /// Name of the struct given to the deserializer, the type name without its path by default
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.options.name = Some(name);
+        self
+    }
+
+    /// What the input should be, reported when it has the wrong type, `struct <name>` by default
+    pub fn expecting<E: ToString>(mut self, expecting: E) -> Self {
+        self.options.expecting = Some(expecting.to_string());
+        self
+    }
+
+    pub fn rename_all(mut self, case: Case) -> Self {
+        self.options.rename_all = Some(case);
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    pub fn key_matching(mut self, key_matching: KeyMatching) -> Self {
+        self.options.key_matching = key_matching;
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    /// Sets the duplicate policy for fields that don't have their own set with `on_duplicate`
+    pub fn duplicate_fields(mut self, duplicate_fields: DuplicateFields) -> Self {
+        self.options.duplicate_fields = duplicate_fields;
+        self
+    }
+
+    /// Reject keys that don't belong to any field instead of ignoring them
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.options.deny_unknown_fields = true;
+        self.options.refresh_field_table(&self.fields);
+        self
+    }
+
+    /// Requires exactly one of the named fields to be present in the input
+    pub fn exactly_one_of(mut self, names: &[&str]) -> Self {
+        self.options
+            .add_group(GroupKind::Exactly, names, &self.fields);
+        self
+    }
+
+    /// Rejects the input if more than one of the named fields is present
+    pub fn at_most_one_of(mut self, names: &[&str]) -> Self {
+        self.options
+            .add_group(GroupKind::AtMost, names, &self.fields);
+        self
+    }
+
+    /// Requires at least one of the named fields to be present in the input
+    pub fn at_least_one_of(mut self, names: &[&str]) -> Self {
+        self.options
+            .add_group(GroupKind::AtLeast, names, &self.fields);
+        self
+    }
+
+    /// Assigns a numeric id to the last added field, matched against integer keys
+    pub fn id(self, id: u64) -> Self {
+        self.update_read_field("id", |field| field.id = Some(id))
+    }
+
+    /// Sets the duplicate policy of the last added field
+    pub fn on_duplicate(self, duplicate_fields: DuplicateFields) -> Self {
+        self.update_read_field("on_duplicate", |field| {
+            field.duplicates = Some(duplicate_fields)
+        })
+    }
+
+    /// Applies `update` to the last added field, or records an error for `deserialize` to report
+    /// when the field is skipped or computed, as it has no value in the input to apply it to
+    fn update_read_field(mut self, method: &str, update: impl FnOnce(&mut FieldSpec)) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = NonEmpty::<FN>::ASSERT;
+        let field = &mut self.fields[FN - 1];
+        match field.is_read() {
+            true => {
+                update(field);
+                self.options.refresh_field_table(&self.fields);
+            }
+            false => self.options.errors.push(format!(
+                "`{method}` was called on a skipped or computed field, which isn't read from the input"
+            )),
+        }
+        self
+    }
 }
 
-impl<T, FBARGS, V: Validator<T>, const FN: usize> StructDeserializer<T, FBARGS, (), V, FN> {
-    pub fn final_builder<FB: FinalBuilder<T, FBARGS>>(
+impl<T, C, FBARGS, FDS, V: Validator<T, C>, const FN: usize>
+    StructDeserializer<T, FBARGS, FDS, (), V, C, FN>
+{
+    pub fn final_builder<FB: FinalBuilder<T, FBARGS, C>>(
         self,
         final_builder: FB,
-    ) -> StructDeserializer<T, FBARGS, FB, V, FN> {
+    ) -> StructDeserializer<T, FBARGS, FDS, FB, V, C, FN> {
         let StructDeserializer {
             target_phantom,
             fb_args_phantom: _,
+            context_phantom: _,
+            decoders,
             final_builder: _,
             validator,
-            field_names,
+            fields,
+            mut options,
         } = self;
+        options.build_field_table(&fields);
         StructDeserializer {
             target_phantom,
             fb_args_phantom: PhantomData,
-            final_builder: Some(final_builder),
+            context_phantom: PhantomData,
+            decoders,
+            final_builder,
             validator,
-            field_names,
+            fields,
+            options,
         }
     }
+
+    /// Builds the value by starting from `T::default()` and applying the setters of the fields
+    /// present in the input, fields must be added with `setter_field`
+    pub fn from_default(self) -> StructDeserializer<T, FBARGS, FDS, Overlay<T>, V, C, FN>
+    where
+        T: Default,
+        Overlay<T>: FinalBuilder<T, FBARGS, C>,
+    {
+        self.final_builder(Overlay(T::default()))
+    }
+
+    /// Same as `from_default`, but starts from `base`
+    pub fn from_base(self, base: T) -> StructDeserializer<T, FBARGS, FDS, Overlay<T>, V, C, FN>
+    where
+        Overlay<T>: FinalBuilder<T, FBARGS, C>,
+    {
+        self.final_builder(Overlay(base))
+    }
 }
 
-impl<T, FBARGS, FB: FinalBuilder<T, FBARGS>, const FN: usize>
-    StructDeserializer<T, FBARGS, FB, (), FN>
-{
-    pub fn validator<V: Validator<T>>(
+impl<T, C, FBARGS, FDS, FB, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, (), C, FN> {
+    pub fn validator<V: Validator<T, C>>(
         self,
         validator: V,
-    ) -> StructDeserializer<T, FBARGS, FB, V, FN> {
+    ) -> StructDeserializer<T, FBARGS, FDS, FB, V, C, FN> {
         let StructDeserializer {
             target_phantom,
             fb_args_phantom,
+            context_phantom,
+            decoders,
             final_builder,
             validator: _,
-            field_names,
+            fields,
+            options,
         } = self;
         StructDeserializer {
             target_phantom,
             fb_args_phantom,
+            context_phantom,
+            decoders,
             final_builder,
-            validator: Some(validator),
-            field_names,
+            validator,
+            fields,
+            options,
         }
     }
 }
 
-impl<T, FB: FinalBuilder<T, ()>, V: Validator<T>> StructDeserializer<T, (), FB, V, 0> {
-    pub fn field<T0, N: ToString>(self, name: N) -> StructDeserializer<T, (T0,), (), V, 1> {
+impl<T, C, V: Validator<T, C>> StructDeserializer<T, (), (), (), V, C, 0> {
+    pub fn field<T0, N: ToString>(
+        self,
+        name: N,
+    ) -> StructDeserializer<T, (T0,), (ValueDecoder,), (), V, C, 1> {
+        self.push_field(FieldSpec::named(name.to_string()), ValueDecoder)
+    }
+
+    pub fn field_id<T0>(
+        self,
+        id: u64,
+    ) -> StructDeserializer<T, (T0,), (ValueDecoder,), (), V, C, 1> {
+        self.push_field(FieldSpec::with_id(id), ValueDecoder)
+    }
+
+    /// Adds a field that produces `None` when it is missing from the input
+    pub fn optional_field<T0, N: ToString>(
+        self,
+        name: N,
+    ) -> StructDeserializer<T, (Option<T0>,), (OptionalDecoder,), (), V, C, 1> {
+        self.push_field(FieldSpec::named(name.to_string()), OptionalDecoder)
+    }
+
+    /// Adds a field read with `W` instead of its `Deserialize` implementation
+    pub fn field_with<W: DeserializeWith<T0>, T0, N: ToString>(
+        self,
+        name: N,
+    ) -> StructDeserializer<T, (T0,), (WithDecoder<W>,), (), V, C, 1> {
+        self.push_field(FieldSpec::named(name.to_string()), WithDecoder(PhantomData))
+    }
+
+    /// Same as `field_with`, but produces `None` when the field is missing from the input
+    pub fn optional_field_with<W: DeserializeWith<T0>, T0, N: ToString>(
+        self,
+        name: N,
+    ) -> StructDeserializer<T, (Option<T0>,), (OptionalWithDecoder<W>,), (), V, C, 1> {
+        self.push_field(
+            FieldSpec::named(name.to_string()),
+            OptionalWithDecoder(PhantomData),
+        )
+    }
+
+    /// Adds a field read as `R` and converted with `convert`, which also receives the context
+    pub fn context_field<T0, R, F: FnMut(&mut C, R) -> T0, N: ToString>(
+        self,
+        name: N,
+        convert: F,
+    ) -> StructDeserializer<T, (T0,), (ContextDecoder<F, R>,), (), V, C, 1> {
+        self.push_field(
+            FieldSpec::named(name.to_string()),
+            ContextDecoder(convert, PhantomData),
+        )
+    }
+
+    /// Adds a field read with a nested `StructDeserializer` sharing the context. The nested
+    /// deserializer reads a single occurrence, so the field can't use `DuplicateFields::Last`.
+    pub fn nested_field<T0, N: ToString, NARGS, NDS, NFB, NV, const NFN: usize>(
+        self,
+        name: N,
+        deserializer: StructDeserializer<T0, NARGS, NDS, NFB, NV, C, NFN>,
+    ) -> StructDeserializer<
+        T,
+        (T0,),
+        (NestedDecoder<StructDeserializer<T0, NARGS, NDS, NFB, NV, C, NFN>>,),
+        (),
+        V,
+        C,
+        1,
+    >
+    where
+        NFB: FinalBuilder<T0, NARGS, C>,
+        NV: Validator<T0, C>,
+    {
+        self.push_field(
+            FieldSpec::named(name.to_string()).nested(),
+            NestedDecoder(Some(deserializer)),
+        )
+    }
+
+    /// Adds a field receiving every occurrence of its key, regardless of the duplicate policy
+    pub fn collect_field<T0, N: ToString>(
+        self,
+        name: N,
+    ) -> StructDeserializer<T, (Vec<T0>,), (CollectDecoder,), (), V, C, 1> {
+        self.push_field(
+            FieldSpec::named(name.to_string()).repeatable(),
+            CollectDecoder,
+        )
+    }
+
+    /// Adds a field applied with `setter` if present in the input, for use with `from_default`
+    /// and `from_base`
+    pub fn setter_field<T0, F: FnOnce(&mut T, T0), N: ToString>(
+        self,
+        name: N,
+        setter: F,
+    ) -> StructDeserializer<T, (FieldUpdate<T0, F>,), (SetterDecoder<F>,), (), V, C, 1> {
+        self.push_field(FieldSpec::named(name.to_string()), SetterDecoder(setter))
+    }
+
+    /// Adds a field that is never read from the input, its value is produced by `value`
+    pub fn skip_field<T0, F: FnOnce() -> T0>(
+        self,
+        value: F,
+    ) -> StructDeserializer<T, (T0,), (SkipDecoder<F>,), (), V, C, 1> {
+        self.push_field(FieldSpec::skipped(), SkipDecoder(value))
+    }
+
+    /// Adds a field that is never read from the input, its value is computed from the arguments of
+    /// the fields added before it
+    pub fn computed_field<T0, F: FnOnce(&()) -> T0>(
+        self,
+        compute: F,
+    ) -> StructDeserializer<T, (T0,), (ComputedDecoder<F>,), (), V, C, 1> {
+        self.push_field(FieldSpec::skipped(), ComputedDecoder(compute))
+    }
+
+    fn push_field<T0, D0>(
+        self,
+        field: FieldSpec,
+        decoder: D0,
+    ) -> StructDeserializer<T, (T0,), (D0,), (), V, C, 1> {
         let StructDeserializer {
             target_phantom,
             fb_args_phantom: _,
-            final_builder: _,
+            context_phantom: _,
+            decoders: _,
+            final_builder,
             validator,
-            field_names: _,
+            fields: _,
+            options,
         } = self;
         StructDeserializer {
             target_phantom,
             fb_args_phantom: PhantomData,
-            final_builder: None,
+            context_phantom: PhantomData,
+            decoders: (decoder,),
+            final_builder,
             validator,
-            field_names: [name.to_string()],
+            fields: [field],
+            options,
         }
     }
 }
 
 macro_rules! add_field_impl {
-    ($($len:expr => ($($n:tt $name1:ident),+), $name2:ident)+) => {
+    ($($len:tt => ($($n:tt $name1:ident $dname1:ident),+), $name2:ident $dname2:ident)+) => {
         $(
-            impl<T, $($name1,)+ FB: FinalBuilder<T, ($($name1,)+)>, V: Validator<T>> StructDeserializer<T, ($($name1,)+), FB, V, $len> {
-                pub fn field<$name2, N: ToString>(self, name: N) -> StructDeserializer<T, ($($name1,)+ $name2), (), V, {$len+1}> {
+            impl<T, C, $($name1,)+ $($dname1,)+ V: Validator<T, C>> StructDeserializer<T, ($($name1,)+), ($($dname1,)+), (), V, C, $len> {
+                pub fn field<$name2, N: ToString>(self, name: N) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ ValueDecoder,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()), ValueDecoder)
+                }
+
+                pub fn field_id<$name2>(self, id: u64) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ ValueDecoder,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::with_id(id), ValueDecoder)
+                }
+
+                /// Adds a field that produces `None` when it is missing from the input
+                pub fn optional_field<$name2, N: ToString>(self, name: N) -> StructDeserializer<T, ($($name1,)+ Option<$name2>,), ($($dname1,)+ OptionalDecoder,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()), OptionalDecoder)
+                }
+
+                /// Adds a field read with `W` instead of its `Deserialize` implementation
+                pub fn field_with<W: DeserializeWith<$name2>, $name2, N: ToString>(self, name: N) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ WithDecoder<W>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()), WithDecoder(PhantomData))
+                }
+
+                /// Same as `field_with`, but produces `None` when the field is missing from the input
+                pub fn optional_field_with<W: DeserializeWith<$name2>, $name2, N: ToString>(self, name: N) -> StructDeserializer<T, ($($name1,)+ Option<$name2>,), ($($dname1,)+ OptionalWithDecoder<W>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()), OptionalWithDecoder(PhantomData))
+                }
+
+                /// Adds a field read as `R` and converted with `convert`, which also receives the
+                /// context
+                pub fn context_field<$name2, R, F: FnMut(&mut C, R) -> $name2, N: ToString>(self, name: N, convert: F) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ ContextDecoder<F, R>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()), ContextDecoder(convert, PhantomData))
+                }
+
+                /// Adds a field read with a nested `StructDeserializer` sharing the context. The
+                /// nested deserializer reads a single occurrence, so the field can't use
+                /// `DuplicateFields::Last`.
+                pub fn nested_field<$name2, N: ToString, NARGS, NDS, NFB: FinalBuilder<$name2, NARGS, C>, NV: Validator<$name2, C>, const NFN: usize>(self, name: N, deserializer: StructDeserializer<$name2, NARGS, NDS, NFB, NV, C, NFN>) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ NestedDecoder<StructDeserializer<$name2, NARGS, NDS, NFB, NV, C, NFN>>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()).nested(), NestedDecoder(Some(deserializer)))
+                }
+
+                /// Adds a field receiving every occurrence of its key, regardless of the duplicate policy
+                pub fn collect_field<$name2, N: ToString>(self, name: N) -> StructDeserializer<T, ($($name1,)+ Vec<$name2>,), ($($dname1,)+ CollectDecoder,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()).repeatable(), CollectDecoder)
+                }
+
+                /// Adds a field applied with `setter` if present in the input, for use with
+                /// `from_default` and `from_base`
+                pub fn setter_field<$name2, F: FnOnce(&mut T, $name2), N: ToString>(self, name: N, setter: F) -> StructDeserializer<T, ($($name1,)+ FieldUpdate<$name2, F>,), ($($dname1,)+ SetterDecoder<F>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::named(name.to_string()), SetterDecoder(setter))
+                }
+
+                /// Adds a field that is never read from the input, its value is produced by `value`
+                pub fn skip_field<$name2, F: FnOnce() -> $name2>(self, value: F) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ SkipDecoder<F>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::skipped(), SkipDecoder(value))
+                }
+
+                /// Adds a field that is never read from the input, its value is computed from the
+                /// arguments of the fields added before it
+                pub fn computed_field<$name2, F: FnOnce(&($($name1,)+)) -> $name2>(self, compute: F) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ ComputedDecoder<F>,), (), V, C, {$len+1}> {
+                    self.push_field(FieldSpec::skipped(), ComputedDecoder(compute))
+                }
+
+                fn push_field<$name2, $dname2>(self, field: FieldSpec, decoder: $dname2) -> StructDeserializer<T, ($($name1,)+ $name2,), ($($dname1,)+ $dname2,), (), V, C, {$len+1}> {
                     let StructDeserializer {
                         target_phantom,
                         fb_args_phantom: _,
-                        final_builder: _,
+                        context_phantom: _,
+                        decoders,
+                        final_builder,
                         validator,
-                        field_names,
+                        fields,
+                        options,
                     } = self;
                     #[allow(clippy::drop_non_drop)]
                     StructDeserializer {
                         target_phantom,
-                        fb_args_phantom: PhantomData::default(),
-                        final_builder: None,
+                        fb_args_phantom: PhantomData,
+                        context_phantom: PhantomData,
+                        decoders: ($(decoders.$n,)+ decoder,),
+                        final_builder,
                         validator,
-                        field_names: concat_arrays!(field_names, [name.to_string()]),
+                        fields: concat_arrays!(fields, [field]),
+                        options,
                     }
                 }
             }
-        )+
-    }
-}
 
-add_field_impl! {
-    1 => (0 T0), T1
-    2 => (0 T0, 1 T1), T2
-    3 => (0 T0, 1 T1, 2 T2), T3
-    4 => (0 T0, 1 T1, 2 T2, 3 T3), T4
-    5 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4), T5
-    6 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5), T6
-    7 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6), T7
-    8 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7), T8
-    9 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8), T9
-    10 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9), T10
-    11 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10), T11
-    12 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11), T12
-    13 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12), T13
-    14 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13), T14
-    15 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14), T15
-}
+            impl<T, C, $($name1,)+ $name2, $($dname1,)+ $dname2: OptionalFieldDecoder, V: Validator<T, C>> StructDeserializer<T, ($($name1,)+ Option<$name2>,), ($($dname1,)+ $dname2,), (), V, C, {$len+1}> {
+                /// Makes the last added optional field required when `predicate` returns true. The
+                /// predicate receives the arguments of the fields added before it, so the condition
+                /// can only depend on those fields. `condition` describes it in errors, in
+                /// `describe()` and in the schema.
+                pub fn required_if<F: FnOnce(&($($name1,)+)) -> bool>(self, condition: &str, predicate: F) -> StructDeserializer<T, ($($name1,)+ Option<$name2>,), ($($dname1,)+ ConditionalDecoder<F, $dname2>,), (), V, C, {$len+1}> {
+                    self.with_requirement(format!("required if {condition}"), predicate, true)
+                }
 
-macro_rules! deserialize_impl {
-    ($($len:expr => $($name:ident),+)+) => {
-        $(
-            impl<T, $($name,)+ FB, V> StructDeserializer<T, ($($name,)+), FB, V, $len>
-            where
-                $($name: for<'a> Deserialize<'a>,)+
-                FB: FinalBuilder<T, ($($name,)+)>,
-                V: Validator<T>,
-            {
-                pub fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+                /// Makes the last added optional field required when `predicate` returns false. The
+                /// predicate receives the arguments of the fields added before it, so the condition
+                /// can only depend on those fields. `condition` describes it in errors, in
+                /// `describe()` and in the schema.
+                pub fn required_unless<F: FnOnce(&($($name1,)+)) -> bool>(self, condition: &str, predicate: F) -> StructDeserializer<T, ($($name1,)+ Option<$name2>,), ($($dname1,)+ ConditionalDecoder<F, $dname2>,), (), V, C, {$len+1}> {
+                    self.with_requirement(format!("required unless {condition}"), predicate, false)
+                }
+
+                fn with_requirement<F>(self, requirement: String, predicate: F, required_when: bool) -> StructDeserializer<T, ($($name1,)+ Option<$name2>,), ($($dname1,)+ ConditionalDecoder<F, $dname2>,), (), V, C, {$len+1}> {
                     let StructDeserializer {
-                        target_phantom: _,
+                        target_phantom,
                         fb_args_phantom: _,
+                        context_phantom: _,
+                        decoders,
                         final_builder,
                         validator,
-                        field_names,
+                        mut fields,
+                        options,
                     } = self;
-                    #[cfg_attr(not(feature = "leaking"), allow(clippy::redundant_clone))]
-                    let field_visitor = FieldVisitor::<T, ($($name,)+), FB, $len>::new(
-                        final_builder.unwrap(),
-                        field_names.clone(),
-                    );
-                    // I don't like this AT ALL
-                    #[cfg(feature = "leaking")]
-                    let field_names_static: &'static [&'static str] = &*field_names
-                        .into_iter()
-                        .map(|s| &*s.leak::<'static>())
-                        .collect::<Vec<_>>()
-                        .leak();
-                    #[cfg(not(feature = "leaking"))]
-                    let field_names_static = &[$(stringify!(field $name)),+];
-                    let value = des.deserialize_struct(std::any::type_name::<T>(), field_names_static, field_visitor).map_err(|e| Error::Deserialization(e))?;
-                    if let Some(validator) = validator {
-                        validator.validate(&value).map_err(|e| Error::Validation(e))?;
+                    fields[$len].requirement = Some(requirement);
+                    StructDeserializer {
+                        target_phantom,
+                        fb_args_phantom: PhantomData,
+                        context_phantom: PhantomData,
+                        decoders: ($(decoders.$n,)+ ConditionalDecoder { decoder: decoders.$len, predicate, required_when },),
+                        final_builder,
+                        validator,
+                        fields,
+                        options,
                     }
-                    Ok(value)
                 }
             }
         )+
     }
 }
 
-deserialize_impl! {
-    1 => T0
-    2 => T0, T1
-    3 => T0, T1, T2
-    4 => T0, T1, T2, T3
-    5 => T0, T1, T2, T3, T4
-    6 => T0, T1, T2, T3, T4, T5
-    7 => T0, T1, T2, T3, T4, T5, T6
-    8 => T0, T1, T2, T3, T4, T5, T6, T7
-    9 => T0, T1, T2, T3, T4, T5, T6, T7, T8
-    10 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9
-    11 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10
-    12 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11
-    13 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12
-    14 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13
-    15 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14
-    16 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15
+add_field_impl! {
+    1 => (0 T0 D0), T1 D1
+    2 => (0 T0 D0, 1 T1 D1), T2 D2
+    3 => (0 T0 D0, 1 T1 D1, 2 T2 D2), T3 D3
+    4 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3), T4 D4
+    5 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4), T5 D5
+    6 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5), T6 D6
+    7 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6), T7 D7
+    8 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7), T8 D8
+    9 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8), T9 D9
+    10 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8, 9 T9 D9), T10 D10
+    11 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8, 9 T9 D9, 10 T10 D10), T11 D11
+    12 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8, 9 T9 D9, 10 T10 D10, 11 T11 D11), T12 D12
+    13 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8, 9 T9 D9, 10 T10 D10, 11 T11 D11, 12 T12 D12), T13 D13
+    14 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8, 9 T9 D9, 10 T10 D10, 11 T11 D11, 12 T12 D12, 13 T13 D13), T14 D14
+    15 => (0 T0 D0, 1 T1 D1, 2 T2 D2, 3 T3 D3, 4 T4 D4, 5 T5 D5, 6 T6 D6, 7 T7 D7, 8 T8 D8, 9 T9 D9, 10 T10 D10, 11 T11 D11, 12 T12 D12, 13 T13 D13, 14 T14 D14), T15 D15
+}
+
+impl<T, C, FBARGS, FDS, FB, V, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, V, C, FN>
+where
+    FDS: FieldDecoders<FBARGS, C>,
+    FB: FinalBuilder<T, FBARGS, C>,
+    V: Validator<T, C>,
+{
+    /// Deserializes with a context that is passed to the field decoders, the final builder and the
+    /// validator
+    pub fn deserialize_with_context<'de, D: Deserializer<'de>>(
+        self,
+        ctx: &mut C,
+        des: D,
+    ) -> Result<T, Error<'de, D>> {
+        let path_scope = PathScope::enter();
+        let (args, final_builder, validator) = self
+            .deserialize_args(ctx, des)
+            .map_err(|e| e.at(path_scope.finish()))?;
+        let value = final_builder.assemble(args, ctx);
+        path_scope.reset();
+        validator
+            .validate(&value, ctx)
+            .map_err(|e| Error::Validation(e, path_scope.finish()))?;
+        Ok(value)
+    }
+
+    /// `DeserializeSeed` deserializing with `ctx`, for use inside other `Deserialize`
+    /// implementations and nested builders
+    pub fn seed(self, ctx: &mut C) -> ContextSeed<'_, Self, C> {
+        ContextSeed {
+            deserializer: self,
+            ctx,
+        }
+    }
+}
+
+impl<T, C, FBARGS, FDS, FB, V, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, V, C, FN>
+where
+    FDS: FieldDecoders<FBARGS, C>,
+    V: Validator<T, C>,
+{
+    fn deserialize_args<'de, D: Deserializer<'de>>(
+        self,
+        ctx: &mut C,
+        des: D,
+    ) -> Result<(FBARGS, FB, V), Error<'de, D>> {
+        let StructDeserializer {
+            target_phantom: _,
+            fb_args_phantom: _,
+            context_phantom: _,
+            decoders,
+            final_builder,
+            validator,
+            mut fields,
+            options:
+                Options {
+                    name,
+                    expecting,
+                    rename_all,
+                    key_matching,
+                    duplicate_fields,
+                    deny_unknown_fields,
+                    groups,
+                    errors,
+                    field_table,
+                },
+        } = self;
+        if let Some(error) = errors.into_iter().next() {
+            return Err(Error::Deserialization(
+                D::Error::custom(error),
+                Path::default(),
+            ));
+        }
+        let name = name.unwrap_or_else(short_type_name::<T>);
+        let field_table = field_table.unwrap_or_else(|| {
+            FieldTable::new(&fields, rename_all, &key_matching, deny_unknown_fields)
+        });
+        if let Some(conflict) = field_table.conflict() {
+            return Err(Error::Deserialization(
+                D::Error::custom(conflict),
+                Path::default(),
+            ));
+        }
+        if let Some(error) = fields
+            .iter()
+            .find_map(|field| field.policy_error(duplicate_fields))
+        {
+            return Err(Error::Deserialization(
+                D::Error::custom(error),
+                Path::default(),
+            ));
+        }
+        if let Some(case) = rename_all {
+            for field in &mut fields {
+                field.name = field.name.as_deref().map(|name| case.apply(name));
+            }
+        }
+        #[cfg(feature = "leaking")]
+        let field_names_static = field_table.static_names();
+        #[cfg(not(feature = "leaking"))]
+        let field_names_static = &PLACEHOLDER_NAMES[..FN];
+        let field_visitor = FieldVisitor::<FBARGS, FDS, C, FN>::new(
+            decoders,
+            ctx,
+            fields,
+            field_table,
+            key_matching,
+            duplicate_fields,
+            groups,
+        )
+        .expecting(name, expecting);
+        let args = des
+            .deserialize_struct(name, field_names_static, field_visitor)
+            .map_err(|e| Error::Deserialization(e, Path::default()))?;
+        Ok((args, final_builder, validator))
+    }
+}
+
+impl<T, FBARGS, FDS, FB, V, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, V, (), FN>
+where
+    FDS: FieldDecoders<FBARGS>,
+    FB: FinalBuilder<T, FBARGS>,
+    V: Validator<T>,
+{
+    pub fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        self.deserialize_with_context(&mut (), des)
+    }
+}
+
+impl<T, FBARGS, FDS, FB, V, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, V, (), FN>
+where
+    FDS: FieldDecoders<FBARGS>,
+    V: Validator<T>,
+{
+    /// Updates `target` with the fields present in the input, fields must be added with
+    /// `setter_field`, no final builder is needed. The validator runs on the updated value, and
+    /// `target` is left untouched if deserialization or validation fails.
+    pub fn deserialize_in_place<'de, D: Deserializer<'de>>(
+        self,
+        target: &mut T,
+        des: D,
+    ) -> Result<(), Error<'de, D>>
+    where
+        T: Clone,
+        FBARGS: ApplyUpdate<T>,
+    {
+        let path_scope = PathScope::enter();
+        let (args, _, validator) = self
+            .deserialize_args(&mut (), des)
+            .map_err(|e| e.at(path_scope.finish()))?;
+        let mut value = target.clone();
+        args.apply(&mut value);
+        path_scope.reset();
+        validator
+            .validate(&value, &mut ())
+            .map_err(|e| Error::Validation(e, path_scope.finish()))?;
+        *target = value;
+        Ok(())
+    }
 }
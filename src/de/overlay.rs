@@ -0,0 +1,94 @@
+use serde::{de::MapAccess, Deserialize};
+
+use super::{path, FieldDecoder, FinalBuilder};
+
+/// Value of a setter field, applied to the target only if the field was present in the input
+pub struct FieldUpdate<FT, F> {
+    value: Option<FT>,
+    setter: F,
+}
+
+pub trait ApplyUpdate<T> {
+    fn apply(self, target: &mut T);
+}
+
+impl<T, FT, F> ApplyUpdate<T> for FieldUpdate<FT, F>
+where
+    F: FnOnce(&mut T, FT),
+{
+    fn apply(self, target: &mut T) {
+        if let Some(value) = self.value {
+            (self.setter)(target, value)
+        }
+    }
+}
+
+/// Reads the field's value and pairs it with a setter instead of passing it positionally
+pub struct SetterDecoder<F>(pub(crate) F);
+
+impl<FT, P, C, F> FieldDecoder<FieldUpdate<FT, F>, P, C> for SetterDecoder<F>
+where
+    FT: for<'a> Deserialize<'a>,
+{
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        _ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        *state = Some(path::next_value(map)?);
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<FieldUpdate<FT, F>> {
+        Some(FieldUpdate {
+            value: state,
+            setter: self.0,
+        })
+    }
+}
+
+/// Final builder starting from a base value and applying the setters of the fields present in the
+/// input
+pub struct Overlay<T>(pub(crate) T);
+
+impl<T, ARGS: ApplyUpdate<T>, C> FinalBuilder<T, ARGS, C> for Overlay<T> {
+    fn assemble(self, args: ARGS, _ctx: &mut C) -> T {
+        let mut value = self.0;
+        args.apply(&mut value);
+        value
+    }
+}
+
+macro_rules! apply_update_impls {
+    ($($len:expr => ($($n:tt $name:ident)+))+) => {
+        $(
+            impl<T, $($name: ApplyUpdate<T>,)+> ApplyUpdate<T> for ($($name,)+) {
+                fn apply(self, target: &mut T) {
+                    $(self.$n.apply(target);)+
+                }
+            }
+        )+
+    }
+}
+
+apply_update_impls! {
+    1 => (0 T0)
+    2 => (0 T0 1 T1)
+    3 => (0 T0 1 T1 2 T2)
+    4 => (0 T0 1 T1 2 T2 3 T3)
+    5 => (0 T0 1 T1 2 T2 3 T3 4 T4)
+    6 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    7 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    8 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    9 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    10 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    11 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10)
+    12 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11)
+    13 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12)
+    14 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13)
+    15 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14)
+    16 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
+}
@@ -0,0 +1,207 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    marker::PhantomData,
+};
+
+use serde::{
+    de::{DeserializeSeed, MapAccess},
+    Deserialize, Deserializer,
+};
+
+/// Location of a value in the input, displayed like `servers[3].tls.port`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Field(String),
+    Index(usize),
+    Variant(String),
+    /// Part of the path inside a plain serde value, which isn't tracked without the
+    /// `value-paths` feature, displayed as `?`
+    Unknown,
+}
+
+impl Path {
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return f.write_str(".");
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Index(index) => write!(f, "[{index}]")?,
+                Segment::Field(name) | Segment::Variant(name) if i == 0 => f.write_str(name)?,
+                Segment::Field(name) | Segment::Variant(name) => write!(f, ".{name}")?,
+                Segment::Unknown if i == 0 => f.write_str("?")?,
+                Segment::Unknown => f.write_str(".?")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// Segments of the value that failed, filled in while the error propagates. `None` when no
+    /// `StructDeserializer` is running on this thread. Only written to when a value fails, reading
+    /// valid input doesn't touch it past the outermost builder.
+    static FAILED_AT: RefCell<Option<Vec<Segment>>> = const { RefCell::new(None) };
+}
+
+/// Path tracking for one call of a builder's `deserialize`, only the outermost call collects the
+/// path, nested ones add their segments to it
+pub(crate) struct PathScope {
+    mark: usize,
+    outermost: bool,
+}
+
+impl PathScope {
+    pub(crate) fn enter() -> Self {
+        FAILED_AT.with(|failed_at| {
+            let mut failed_at = failed_at.borrow_mut();
+            match &*failed_at {
+                Some(segments) => Self {
+                    mark: segments.len(),
+                    outermost: false,
+                },
+                None => {
+                    *failed_at = Some(Vec::new());
+                    Self {
+                        mark: 0,
+                        outermost: true,
+                    }
+                }
+            }
+        })
+    }
+
+    /// Drops segments left behind by errors that were recovered from
+    pub(crate) fn reset(&self) {
+        reset(self.mark);
+    }
+
+    /// Path of the failure, empty for nested calls as their caller reports it
+    pub(crate) fn finish(&self) -> Path {
+        if !self.outermost {
+            return Path::default();
+        }
+        let segments = FAILED_AT.with(|failed_at| failed_at.borrow_mut().take());
+        Path {
+            segments: segments.unwrap_or_default(),
+        }
+    }
+}
+
+impl Drop for PathScope {
+    fn drop(&mut self) {
+        if self.outermost {
+            FAILED_AT.with(|failed_at| *failed_at.borrow_mut() = None);
+        }
+    }
+}
+
+/// Current depth of the path, for visitors to insert their own segment on failure
+pub(crate) fn mark() -> usize {
+    FAILED_AT.with(|failed_at| failed_at.borrow().as_ref().map_or(0, Vec::len))
+}
+
+pub(crate) fn reset(mark: usize) {
+    FAILED_AT.with(|failed_at| {
+        if let Some(segments) = &mut *failed_at.borrow_mut() {
+            segments.truncate(mark);
+        }
+    });
+}
+
+/// Records that the value at `segment` failed, `mark` being the depth of the parent
+pub(crate) fn insert(mark: usize, segment: Segment) {
+    FAILED_AT.with(|failed_at| {
+        if let Some(segments) = &mut *failed_at.borrow_mut() {
+            segments.insert(mark.min(segments.len()), segment);
+        }
+    });
+}
+
+#[cfg(feature = "value-paths")]
+fn extend(tail: impl Iterator<Item = Segment>) {
+    FAILED_AT.with(|failed_at| {
+        if let Some(segments) = &mut *failed_at.borrow_mut() {
+            segments.extend(tail);
+        }
+    });
+}
+
+/// Reads the next map value, recording where it failed inside the value
+pub(crate) fn next_value<'de, FT, A>(map: &mut A) -> Result<FT, A::Error>
+where
+    FT: Deserialize<'de>,
+    A: MapAccess<'de>,
+{
+    map.next_value_seed(TrackedSeed(PhantomData))
+}
+
+/// Same as `next_value`, reading the value with `seed`
+pub(crate) fn next_value_seed<'de, S, A>(map: &mut A, seed: S) -> Result<S::Value, A::Error>
+where
+    S: DeserializeSeed<'de>,
+    A: MapAccess<'de>,
+{
+    map.next_value_seed(TrackedSeed(seed))
+}
+
+/// Reads a field's value. Without the `value-paths` feature the path stops at the field, or goes
+/// on in the builders running inside the value after a `Segment::Unknown`, as the value may have
+/// led to them through sequences or maps that aren't tracked. With it, the seed runs through
+/// `serde_path_to_error` to know where a failure happened inside any value, at the cost of a
+/// `String` for every map key read.
+pub(crate) struct TrackedSeed<S>(pub(crate) S);
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for TrackedSeed<S> {
+    type Value = S::Value;
+
+    #[cfg(not(feature = "value-paths"))]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        let mark = mark();
+        let result = self.0.deserialize(deserializer);
+        if result.is_err() && self::mark() > mark {
+            insert(mark, Segment::Unknown);
+        }
+        result
+    }
+
+    #[cfg(feature = "value-paths")]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        let mark = mark();
+        let mut track = serde_path_to_error::Track::new();
+        let result = self.0.deserialize(serde_path_to_error::Deserializer::new(
+            deserializer,
+            &mut track,
+        ));
+        if result.is_err() {
+            // builders running inside the value have already been seen by the tracker
+            reset(mark);
+            extend(track.path().iter().map(Segment::from));
+        }
+        result
+    }
+}
+
+#[cfg(feature = "value-paths")]
+impl From<&serde_path_to_error::Segment> for Segment {
+    fn from(segment: &serde_path_to_error::Segment) -> Self {
+        match segment {
+            serde_path_to_error::Segment::Seq { index } => Self::Index(*index),
+            serde_path_to_error::Segment::Map { key } => Self::Field(key.clone()),
+            serde_path_to_error::Segment::Enum { variant } => Self::Variant(variant.clone()),
+            serde_path_to_error::Segment::Unknown => Self::Unknown,
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::borrow::Cow;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+
+use crate::short_type_name;
+
+use super::{
+    field_group::{FieldGroup, GroupKind},
+    CollectDecoder, ComputedDecoder, ConditionalDecoder, ContextDecoder, FieldUpdate,
+    NestedDecoder, OptionalDecoder, OptionalWithDecoder, SetterDecoder, SkipDecoder,
+    StructDeserializer, Validator, ValueDecoder, WithDecoder,
+};
+
+/// Schema of the value a field decoder reads for `FT`
+pub trait FieldSchema<FT> {
+    /// The schema and whether the field is required, `None` for fields not read from the input
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)>;
+}
+
+impl<FT: JsonSchema> FieldSchema<FT> for ValueDecoder {
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((generator.subschema_for::<FT>(), true))
+    }
+}
+
+impl<FT: JsonSchema> FieldSchema<Option<FT>> for OptionalDecoder {
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((generator.subschema_for::<FT>(), false))
+    }
+}
+
+/// The representation produced by `W` is unknown, so any value is accepted
+impl<FT, W> FieldSchema<FT> for WithDecoder<W> {
+    fn field_schema(&self, _generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((Schema::from(true), true))
+    }
+}
+
+impl<FT, W> FieldSchema<Option<FT>> for OptionalWithDecoder<W> {
+    fn field_schema(&self, _generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((Schema::from(true), false))
+    }
+}
+
+/// The condition can't be expressed in the schema, it is only described
+impl<FT, F, D: FieldSchema<Option<FT>>> FieldSchema<Option<FT>> for ConditionalDecoder<F, D> {
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        self.decoder.field_schema(generator)
+    }
+}
+
+impl<FT: JsonSchema> FieldSchema<Vec<FT>> for CollectDecoder {
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((generator.subschema_for::<FT>(), false))
+    }
+}
+
+impl<FT, F> FieldSchema<FT> for SkipDecoder<F> {
+    fn field_schema(&self, _generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        None
+    }
+}
+
+impl<FT, F> FieldSchema<FT> for ComputedDecoder<F> {
+    fn field_schema(&self, _generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        None
+    }
+}
+
+impl<FT: JsonSchema, F> FieldSchema<FieldUpdate<FT, F>> for SetterDecoder<F> {
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((generator.subschema_for::<FT>(), false))
+    }
+}
+
+impl<FT, F, R: JsonSchema> FieldSchema<FT> for ContextDecoder<F, R> {
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        Some((generator.subschema_for::<R>(), true))
+    }
+}
+
+impl<FT, FBARGS, FDS, FB, V, C, const FN: usize> FieldSchema<FT>
+    for NestedDecoder<StructDeserializer<FT, FBARGS, FDS, FB, V, C, FN>>
+where
+    FDS: FieldSchemas<FBARGS>,
+    V: Validator<FT, C>,
+{
+    fn field_schema(&self, generator: &mut SchemaGenerator) -> Option<(Schema, bool)> {
+        let schema = match &self.0 {
+            Some(deserializer) => deserializer.json_schema_in(generator),
+            None => Schema::from(true),
+        };
+        Some((schema, true))
+    }
+}
+
+/// Tuple of field decoders that can describe the fields producing `ARGS`
+pub trait FieldSchemas<ARGS> {
+    fn field_schemas(&self, generator: &mut SchemaGenerator) -> Vec<Option<(Schema, bool)>>;
+}
+
+impl FieldSchemas<()> for () {
+    fn field_schemas(&self, _generator: &mut SchemaGenerator) -> Vec<Option<(Schema, bool)>> {
+        Vec::new()
+    }
+}
+
+macro_rules! field_schemas_impl {
+    ($($len:expr => ($($n:tt $name:ident $dtype:ident)+))+) => {
+        $(
+            impl<$($name, $dtype: FieldSchema<$name>,)+> FieldSchemas<($($name,)+)> for ($($dtype,)+) {
+                fn field_schemas(&self, generator: &mut SchemaGenerator) -> Vec<Option<(Schema, bool)>> {
+                    vec![$(self.$n.field_schema(generator),)+]
+                }
+            }
+        )+
+    }
+}
+
+field_schemas_impl! {
+    1 => (0 T0 D0)
+    2 => (0 T0 D0 1 T1 D1)
+    3 => (0 T0 D0 1 T1 D1 2 T2 D2)
+    4 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3)
+    5 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4)
+    6 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5)
+    7 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6)
+    8 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7)
+    9 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8)
+    10 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9)
+    11 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10)
+    12 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11)
+    13 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12)
+    14 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12 13 T13 D13)
+    15 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12 13 T13 D13 14 T14 D14)
+    16 => (0 T0 D0 1 T1 D1 2 T2 D2 3 T3 D3 4 T4 D4 5 T5 D5 6 T6 D6 7 T7 D7 8 T8 D8 9 T9 D9 10 T10 D10 11 T11 D11 12 T12 D12 13 T13 D13 14 T14 D14 15 T15 D15)
+}
+
+impl<T, FBARGS, FDS, FB, V, C, const FN: usize> StructDeserializer<T, FBARGS, FDS, FB, V, C, FN>
+where
+    FDS: FieldSchemas<FBARGS>,
+    V: Validator<T, C>,
+{
+    /// JSON Schema document describing the input accepted by the builder
+    pub fn json_schema(&self) -> Schema {
+        let mut generator = SchemaGenerator::default();
+        let mut schema = self.json_schema_in(&mut generator);
+        let object = schema.ensure_object();
+        let title = self.options.name.unwrap_or_else(short_type_name::<T>);
+        object.insert("title".into(), title.into());
+        if let Some(expecting) = &self.options.expecting {
+            object.insert("description".into(), expecting.as_str().into());
+        }
+        if let Some(meta_schema) = &generator.settings().meta_schema {
+            object.insert("$schema".into(), meta_schema.as_ref().into());
+        }
+        let definitions = generator.take_definitions(true);
+        if !definitions.is_empty() {
+            let path = generator.settings().definitions_path.clone();
+            let mut target = Some(object);
+            for segment in path.trim_start_matches('/').split('/') {
+                // a path going through a keyword of the struct's schema leaves the definitions out
+                target = target.and_then(|target| {
+                    target
+                        .entry(segment)
+                        .or_insert_with(|| Value::Object(Map::new()))
+                        .as_object_mut()
+                });
+            }
+            if let Some(target) = target {
+                target.extend(definitions);
+            }
+        }
+        schema
+    }
+
+    /// Schema of the accepted input, the types it refers to are added to the definitions of
+    /// `generator`
+    pub fn json_schema_in(&self, generator: &mut SchemaGenerator) -> Schema {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let field_schemas = self.decoders.field_schemas(generator);
+        let names: Vec<Option<Cow<str>>> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let name = field.name.as_deref()?;
+                Some(match self.options.rename_all {
+                    Some(case) => Cow::Owned(case.apply(name)),
+                    None => Cow::Borrowed(name),
+                })
+            })
+            .collect();
+        for ((field, name), field_schema) in self.fields.iter().zip(&names).zip(field_schemas) {
+            let (Some(name), Some((mut schema, is_required))) = (name, field_schema) else {
+                continue;
+            };
+            if let Some(requirement) = &field.requirement {
+                schema
+                    .ensure_object()
+                    .insert("description".into(), requirement.as_str().into());
+            }
+            if is_required {
+                required.push(Value::from(name.as_ref()));
+            }
+            properties.insert(name.to_string(), schema.into());
+        }
+
+        let mut schema = Schema::from(Map::new());
+        schema.insert("type".into(), "object".into());
+        schema.insert("properties".into(), properties.into());
+        if !required.is_empty() {
+            schema.insert("required".into(), required.into());
+        }
+        if self.options.deny_unknown_fields {
+            schema.insert("additionalProperties".into(), false.into());
+        }
+        let groups: Vec<Value> = self
+            .options
+            .groups
+            .iter()
+            .map(|group| group_schema(group, &names))
+            .collect();
+        if !groups.is_empty() {
+            schema.insert("allOf".into(), groups.into());
+        }
+        let keys = SchemaKeys {
+            fields: self
+                .fields
+                .iter()
+                .zip(&names)
+                .filter_map(|(field, key)| Some((field.name.as_deref()?, key.as_deref()?)))
+                .collect(),
+        };
+        self.validator.constrain_schema(&mut schema, &keys);
+        schema
+    }
+}
+
+/// Keys of the properties of a struct's schema, for validators constraining the schema of a field
+/// selected by name
+#[derive(Default)]
+pub struct SchemaKeys<'a> {
+    /// Names the fields were added with and their keys once `rename_all` is applied
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl SchemaKeys<'_> {
+    /// Key of the property of the field added as `name`, `name` itself if no field was added
+    /// with it
+    pub fn key<'b>(&'b self, name: &'b str) -> &'b str {
+        self.fields
+            .iter()
+            .find_map(|&(field, key)| (field == name).then_some(key))
+            .unwrap_or(name)
+    }
+}
+
+fn group_schema(group: &FieldGroup, names: &[Option<Cow<str>>]) -> Value {
+    let members: Vec<&str> = group
+        .members
+        .iter()
+        .filter_map(|&member| names[member].as_deref())
+        .collect();
+    match group.kind {
+        GroupKind::Exactly => json!({
+            "oneOf": members.iter().map(|name| json!({"required": [name]})).collect::<Vec<_>>()
+        }),
+        GroupKind::AtLeast => json!({
+            "anyOf": members.iter().map(|name| json!({"required": [name]})).collect::<Vec<_>>()
+        }),
+        GroupKind::AtMost => {
+            let pairs = members.iter().enumerate().flat_map(|(i, first)| {
+                members[i + 1..]
+                    .iter()
+                    .map(move |second| json!({"required": [first, second]}))
+            });
+            json!({"not": {"anyOf": pairs.collect::<Vec<_>>()}})
+        }
+    }
+}
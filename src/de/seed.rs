@@ -0,0 +1,91 @@
+use std::marker::PhantomData;
+
+use serde::{
+    de::{DeserializeSeed, Error as _, MapAccess},
+    Deserialize, Deserializer,
+};
+
+use super::{
+    error::Error, path, FieldDecoder, FieldDecoders, FinalBuilder, StructDeserializer, Validator,
+};
+
+/// `DeserializeSeed` running a `StructDeserializer` with a context, validation errors are reported
+/// as custom errors of the deserializer
+pub struct ContextSeed<'c, S, C> {
+    pub(crate) deserializer: S,
+    pub(crate) ctx: &'c mut C,
+}
+
+impl<'de, 'c, T, C, FBARGS, FDS, FB, V, const FN: usize> DeserializeSeed<'de>
+    for ContextSeed<'c, StructDeserializer<T, FBARGS, FDS, FB, V, C, FN>, C>
+where
+    FDS: FieldDecoders<FBARGS, C>,
+    FB: FinalBuilder<T, FBARGS, C>,
+    V: Validator<T, C>,
+{
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        self.deserializer
+            .deserialize_with_context(self.ctx, deserializer)
+            .map_err(Error::into_de_error)
+    }
+}
+
+/// Reads the field's value with its `Deserialize` implementation and converts it with a closure
+/// receiving the context
+pub struct ContextDecoder<F, R>(pub(crate) F, pub(crate) PhantomData<fn() -> R>);
+
+impl<FT, P, C, F, R> FieldDecoder<FT, P, C> for ContextDecoder<F, R>
+where
+    R: for<'a> Deserialize<'a>,
+    F: FnMut(&mut C, R) -> FT,
+{
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        let raw = path::next_value(map)?;
+        *state = Some((self.0)(ctx, raw));
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<FT> {
+        state
+    }
+}
+
+/// Reads the field's value with a nested `StructDeserializer` sharing the context
+pub struct NestedDecoder<S>(pub(crate) Option<S>);
+
+impl<FT, P, C, FBARGS, FDS, FB, V, const FN: usize> FieldDecoder<FT, P, C>
+    for NestedDecoder<StructDeserializer<FT, FBARGS, FDS, FB, V, C, FN>>
+where
+    FDS: FieldDecoders<FBARGS, C>,
+    FB: FinalBuilder<FT, FBARGS, C>,
+    V: Validator<FT, C>,
+{
+    type State = Option<FT>;
+
+    fn decode<'de, A: MapAccess<'de>>(
+        &mut self,
+        state: &mut Self::State,
+        map: &mut A,
+        ctx: &mut C,
+    ) -> Result<(), A::Error> {
+        let deserializer = self
+            .0
+            .take()
+            .ok_or_else(|| A::Error::custom("a nested field can only be read once"))?;
+        *state = Some(map.next_value_seed(deserializer.seed(ctx))?);
+        Ok(())
+    }
+
+    fn finish(self, state: Self::State, _prefix: &P) -> Option<FT> {
+        state
+    }
+}
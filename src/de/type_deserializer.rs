@@ -0,0 +1,39 @@
+use serde::Deserializer;
+
+use super::{
+    error::Error, DynFields, DynStructDeserializer, FieldDecoders, FinalBuilder,
+    StructDeserializer, Validator, VersionedDeserializer,
+};
+
+/// Builder that deserializes a `T`, implemented by the deserializers of this crate
+pub trait TypeDeserializer<T> {
+    fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>>;
+}
+
+impl<T, FBARGS, FDS, FB, V, const FN: usize> TypeDeserializer<T>
+    for StructDeserializer<T, FBARGS, FDS, FB, V, (), FN>
+where
+    FDS: FieldDecoders<FBARGS>,
+    FB: FinalBuilder<T, FBARGS>,
+    V: Validator<T>,
+{
+    fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        self.deserialize(des)
+    }
+}
+
+impl<'a, T, FB, V> TypeDeserializer<T> for DynStructDeserializer<'a, T, FB, V>
+where
+    FB: FinalBuilder<T, (DynFields,)>,
+    V: Validator<T>,
+{
+    fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        self.deserialize(des)
+    }
+}
+
+impl<'a, T> TypeDeserializer<T> for VersionedDeserializer<'a, T> {
+    fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        self.deserialize(des)
+    }
+}
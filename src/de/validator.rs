@@ -1,15 +1,136 @@
-pub trait Validator<T> {
-    fn validate(self, value: &T) -> Result<(), String>;
+use super::WithContext;
+
+pub trait Validator<T, C = ()> {
+    fn validate(self, value: &T, ctx: &mut C) -> Result<(), String>;
+
+    /// Adds the constraints checked by the validator to the schema of the value it validates
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, _schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {}
 }
 
-impl<T> Validator<T> for () {
-    fn validate(self, _value: &T) -> Result<(), String> {
+impl<T, C> Validator<T, C> for () {
+    fn validate(self, _value: &T, _ctx: &mut C) -> Result<(), String> {
         Ok(())
     }
 }
 
-impl<T, FN: FnOnce(&T) -> Result<(), String>> Validator<T> for FN {
-    fn validate(self, value: &T) -> Result<(), String> {
+impl<T, C, FN: FnOnce(&T) -> Result<(), String>> Validator<T, C> for FN {
+    fn validate(self, value: &T, _ctx: &mut C) -> Result<(), String> {
         self(value)
     }
 }
+
+impl<T, C, FN: FnOnce(&mut C, &T) -> Result<(), String>> Validator<T, C> for WithContext<FN> {
+    fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+        (self.0)(ctx, value)
+    }
+}
+
+/// Runs the first validator, then the second one if the first passed
+pub struct And<A, B>(pub A, pub B);
+
+impl<T, C, A: Validator<T, C>, B: Validator<T, C>> Validator<T, C> for And<A, B> {
+    fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+        self.0.validate(value, ctx)?;
+        self.1.validate(value, ctx)
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, keys: &super::SchemaKeys) {
+        self.0.constrain_schema(schema, keys);
+        self.1.constrain_schema(schema, keys);
+    }
+}
+
+/// Passes if either validator passes, the second one only runs if the first failed
+pub struct Or<A, B>(pub A, pub B);
+
+impl<T, C, A: Validator<T, C>, B: Validator<T, C>> Validator<T, C> for Or<A, B> {
+    fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+        let Err(first) = self.0.validate(value, ctx) else {
+            return Ok(());
+        };
+        self.1
+            .validate(value, ctx)
+            .map_err(|second| format!("none of the alternatives passed: {first}; {second}"))
+    }
+}
+
+/// Runs every validator of a tuple, reporting all the failures
+pub struct AllOf<VS>(pub VS);
+
+/// Passes if any validator of a tuple passes, stopping at the first one that does
+pub struct AnyOf<VS>(pub VS);
+
+macro_rules! tuple_validators_impl {
+    ($($len:expr => ($($vtype:ident $v:ident)+))+) => {
+        $(
+            impl<T, C, $($vtype: Validator<T, C>,)+> Validator<T, C> for AllOf<($($vtype,)+)> {
+                fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+                    let AllOf(($($v,)+)) = self;
+                    let errors: Vec<String> = [$($v.validate(value, ctx).err(),)+].into_iter().flatten().collect();
+                    match errors.is_empty() {
+                        true => Ok(()),
+                        false => Err(errors.join("; ")),
+                    }
+                }
+
+                #[cfg(feature = "schema")]
+                fn constrain_schema(&self, schema: &mut schemars::Schema, keys: &super::SchemaKeys) {
+                    let AllOf(($($v,)+)) = self;
+                    $($v.constrain_schema(schema, keys);)+
+                }
+            }
+
+            impl<T, C, $($vtype: Validator<T, C>,)+> Validator<T, C> for AnyOf<($($vtype,)+)> {
+                fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+                    let AnyOf(($($v,)+)) = self;
+                    let mut errors = Vec::new();
+                    $(
+                        match $v.validate(value, ctx) {
+                            Ok(()) => return Ok(()),
+                            Err(error) => errors.push(error),
+                        }
+                    )+
+                    Err(format!("none of the alternatives passed: {}", errors.join("; ")))
+                }
+            }
+        )+
+    }
+}
+
+tuple_validators_impl! {
+    1 => (V0 v0)
+    2 => (V0 v0 V1 v1)
+    3 => (V0 v0 V1 v1 V2 v2)
+    4 => (V0 v0 V1 v1 V2 v2 V3 v3)
+    5 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4)
+    6 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5)
+    7 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6)
+    8 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7)
+    9 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8)
+    10 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9)
+    11 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9 V10 v10)
+    12 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9 V10 v10 V11 v11)
+    13 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9 V10 v10 V11 v11 V12 v12)
+    14 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9 V10 v10 V11 v11 V12 v12 V13 v13)
+    15 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9 V10 v10 V11 v11 V12 v12 V13 v13 V14 v14)
+    16 => (V0 v0 V1 v1 V2 v2 V3 v3 V4 v4 V5 v5 V6 v6 V7 v7 V8 v8 V9 v9 V10 v10 V11 v11 V12 v12 V13 v13 V14 v14 V15 v15)
+}
+
+/// Prefixes the failures of a validator with the name of the rule it checks
+pub struct Named<V>(pub &'static str, pub V);
+
+impl<T, C, V: Validator<T, C>> Validator<T, C> for Named<V> {
+    fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+        let Named(name, validator) = self;
+        validator
+            .validate(value, ctx)
+            .map_err(|error| format!("{name}: {error}"))
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, keys: &super::SchemaKeys) {
+        self.1.constrain_schema(schema, keys);
+    }
+}
@@ -0,0 +1,440 @@
+//! Common checks, usable as validators of a field's value or of a whole struct.
+//!
+//! Field validators such as `InRange` or `NonEmpty` validate the value of one field, `OnField`
+//! applies them to a struct by selecting the field with an accessor. With the `schema` feature,
+//! they add their constraints to the schema of the field.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Display,
+    hash::Hash,
+    ops::{Bound, RangeBounds},
+};
+
+use serde::Serialize;
+
+use super::Validator;
+
+/// Applies a validator to the field of a struct selected by an accessor, failures are prefixed
+/// with `name`. The schema constraints are applied to the property of the field added to the
+/// builder as `name`, renamed by `rename_all`, or to the property keyed `name`.
+pub struct OnField<A, V> {
+    name: &'static str,
+    accessor: A,
+    validator: V,
+}
+
+impl<A, V> OnField<A, V> {
+    pub fn new<T, FT>(name: &'static str, accessor: A, validator: V) -> Self
+    where
+        A: FnOnce(&T) -> &FT,
+    {
+        Self {
+            name,
+            accessor,
+            validator,
+        }
+    }
+}
+
+impl<T, C, FT, A, V> Validator<T, C> for OnField<A, V>
+where
+    A: FnOnce(&T) -> &FT,
+    V: Validator<FT, C>,
+{
+    fn validate(self, value: &T, ctx: &mut C) -> Result<(), String> {
+        let OnField {
+            name,
+            accessor,
+            validator,
+        } = self;
+        validator
+            .validate(accessor(value), ctx)
+            .map_err(|error| format!("{name}: {error}"))
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, keys: &super::SchemaKeys) {
+        let property = schema
+            .get_mut("properties")
+            .and_then(|properties| properties.get_mut(keys.key(self.name)))
+            .and_then(|property| <&mut schemars::Schema>::try_from(property).ok());
+        if let Some(property) = property {
+            self.validator
+                .constrain_schema(property, &super::SchemaKeys::default());
+        }
+    }
+}
+
+/// Requires a number to be within a range
+pub struct InRange<R>(pub R);
+
+impl<N, C, R> Validator<N, C> for InRange<R>
+where
+    N: PartialOrd + Display,
+    R: RangeBounds<N>,
+{
+    fn validate(self, value: &N, _ctx: &mut C) -> Result<(), String> {
+        match self.0.contains(value) {
+            true => Ok(()),
+            false => Err(format!(
+                "{value} is out of range, expected {}",
+                describe_range(&self.0)
+            )),
+        }
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {
+        // the bounds go through their text form as numbers have no common conversion
+        let number = |bound: &N| bound.to_string().parse::<serde_json::Number>().ok();
+        let minimum = match self.0.start_bound() {
+            Bound::Included(min) => Some(("minimum", min)),
+            Bound::Excluded(min) => Some(("exclusiveMinimum", min)),
+            Bound::Unbounded => None,
+        };
+        let maximum = match self.0.end_bound() {
+            Bound::Included(max) => Some(("maximum", max)),
+            Bound::Excluded(max) => Some(("exclusiveMaximum", max)),
+            Bound::Unbounded => None,
+        };
+        for (keyword, bound) in minimum.into_iter().chain(maximum) {
+            if let Some(bound) = number(bound) {
+                schema.insert(keyword.into(), bound.into());
+            }
+        }
+    }
+}
+
+fn describe_range<N: Display>(range: &impl RangeBounds<N>) -> String {
+    let start = match range.start_bound() {
+        Bound::Included(min) => Some(format!("at least {min}")),
+        Bound::Excluded(min) => Some(format!("more than {min}")),
+        Bound::Unbounded => None,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(max) => Some(format!("at most {max}")),
+        Bound::Excluded(max) => Some(format!("less than {max}")),
+        Bound::Unbounded => None,
+    };
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{start} and {end}"),
+        (Some(bound), None) | (None, Some(bound)) => bound,
+        (None, None) => "any value".to_owned(),
+    }
+}
+
+/// What the length of a value counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Characters,
+    Items,
+    Entries,
+}
+
+/// Value with a length, checked by `Length` and `NonEmpty`
+pub trait HasLength {
+    const UNIT: LengthUnit;
+
+    fn length(&self) -> usize;
+}
+
+impl HasLength for String {
+    const UNIT: LengthUnit = LengthUnit::Characters;
+
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+macro_rules! has_length_impls {
+    ($($unit:ident => $($ty:ident<$($param:ident),+>)+;)+) => {
+        $($(
+            impl<$($param),+> HasLength for $ty<$($param),+> {
+                const UNIT: LengthUnit = LengthUnit::$unit;
+
+                fn length(&self) -> usize {
+                    self.len()
+                }
+            }
+        )+)+
+    };
+}
+
+has_length_impls! {
+    Items => Vec<T> VecDeque<T> HashSet<T, S> BTreeSet<T>;
+    Entries => HashMap<K, V, S> BTreeMap<K, V>;
+}
+
+/// Requires the length of a string or collection to be within a range, strings are measured in
+/// characters
+pub struct Length<R>(pub R);
+
+impl<FT, C, R> Validator<FT, C> for Length<R>
+where
+    FT: HasLength,
+    R: RangeBounds<usize>,
+{
+    fn validate(self, value: &FT, _ctx: &mut C) -> Result<(), String> {
+        let length = value.length();
+        match self.0.contains(&length) {
+            true => Ok(()),
+            false => Err(format!(
+                "length {length} is out of range, expected {}",
+                describe_range(&self.0)
+            )),
+        }
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {
+        let (min_keyword, max_keyword) = length_keywords(FT::UNIT);
+        let min = match self.0.start_bound() {
+            Bound::Included(&min) => Some(min),
+            Bound::Excluded(&min) => min.checked_add(1),
+            Bound::Unbounded => None,
+        };
+        let max = match self.0.end_bound() {
+            Bound::Included(&max) => Some(max),
+            Bound::Excluded(&max) => max.checked_sub(1),
+            Bound::Unbounded => None,
+        };
+        if let Some(min) = min {
+            schema.insert(min_keyword.into(), min.into());
+        }
+        if let Some(max) = max {
+            schema.insert(max_keyword.into(), max.into());
+        }
+    }
+}
+
+#[cfg(feature = "schema")]
+fn length_keywords(unit: LengthUnit) -> (&'static str, &'static str) {
+    match unit {
+        LengthUnit::Characters => ("minLength", "maxLength"),
+        LengthUnit::Items => ("minItems", "maxItems"),
+        LengthUnit::Entries => ("minProperties", "maxProperties"),
+    }
+}
+
+/// Requires a string or collection not to be empty
+pub struct NonEmpty;
+
+impl<FT: HasLength, C> Validator<FT, C> for NonEmpty {
+    fn validate(self, value: &FT, _ctx: &mut C) -> Result<(), String> {
+        match value.length() {
+            0 => Err("must not be empty".to_owned()),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {
+        schema.insert(length_keywords(FT::UNIT).0.into(), 1.into());
+    }
+}
+
+/// Requires the elements of a list to be distinct
+pub struct Unique;
+
+impl<T: Eq + Hash, C> Validator<Vec<T>, C> for Unique {
+    fn validate(self, value: &Vec<T>, _ctx: &mut C) -> Result<(), String> {
+        let mut seen = HashSet::with_capacity(value.len());
+        match value.iter().position(|element| !seen.insert(element)) {
+            Some(index) => Err(format!("element at index {index} is a duplicate")),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {
+        schema.insert("uniqueItems".into(), true.into());
+    }
+}
+
+/// Requires a value to be one of a set of allowed values
+pub struct OneOf<V: 'static>(pub &'static [V]);
+
+impl<FT, C, V> Validator<FT, C> for OneOf<V>
+where
+    FT: PartialEq<V>,
+    V: Display + Serialize,
+{
+    fn validate(self, value: &FT, _ctx: &mut C) -> Result<(), String> {
+        if self.0.iter().any(|allowed| value == allowed) {
+            return Ok(());
+        }
+        let allowed: Vec<String> = self
+            .0
+            .iter()
+            .map(|allowed| format!("`{allowed}`"))
+            .collect();
+        Err(format!("must be one of {}", allowed.join(", ")))
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {
+        let allowed: Result<Vec<_>, _> = self.0.iter().map(serde_json::to_value).collect();
+        if let Ok(allowed) = allowed {
+            schema.insert("enum".into(), allowed.into());
+        }
+    }
+}
+
+/// Set of characters accepted by `Chars`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    AsciiLowercase,
+    AsciiUppercase,
+    AsciiDigit,
+    Char(char),
+    /// Characters between the two bounds, inclusive
+    Range(char, char),
+}
+
+impl CharClass {
+    fn contains(self, c: char) -> bool {
+        match self {
+            Self::AsciiLowercase => c.is_ascii_lowercase(),
+            Self::AsciiUppercase => c.is_ascii_uppercase(),
+            Self::AsciiDigit => c.is_ascii_digit(),
+            Self::Char(allowed) => c == allowed,
+            Self::Range(start, end) => (start..=end).contains(&c),
+        }
+    }
+}
+
+impl Display for CharClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AsciiLowercase => f.write_str("a-z"),
+            Self::AsciiUppercase => f.write_str("A-Z"),
+            Self::AsciiDigit => f.write_str("0-9"),
+            Self::Char(c) => write!(f, "{c:?}"),
+            Self::Range(start, end) => write!(f, "{start:?}-{end:?}"),
+        }
+    }
+}
+
+/// Requires every character of a string to belong to one of the classes
+pub struct Chars(pub &'static [CharClass]);
+
+impl<FT: AsRef<str>, C> Validator<FT, C> for Chars {
+    fn validate(self, value: &FT, _ctx: &mut C) -> Result<(), String> {
+        let Some((index, c)) = value
+            .as_ref()
+            .chars()
+            .enumerate()
+            .find(|&(_, c)| !self.0.iter().any(|class| class.contains(c)))
+        else {
+            return Ok(());
+        };
+        let classes: Vec<String> = self.0.iter().map(CharClass::to_string).collect();
+        Err(format!(
+            "character {c:?} at {index} is not allowed, expected {}",
+            classes.join(", ")
+        ))
+    }
+
+    #[cfg(feature = "schema")]
+    fn constrain_schema(&self, schema: &mut schemars::Schema, _keys: &super::SchemaKeys) {
+        let escape = |c: char| match c {
+            '\\' | ']' | '[' | '^' | '-' => format!("\\{c}"),
+            c => c.to_string(),
+        };
+        let classes: String = self
+            .0
+            .iter()
+            .map(|class| match *class {
+                CharClass::AsciiLowercase => "a-z".to_owned(),
+                CharClass::AsciiUppercase => "A-Z".to_owned(),
+                CharClass::AsciiDigit => "0-9".to_owned(),
+                CharClass::Char(c) => escape(c),
+                CharClass::Range(start, end) => format!("{}-{}", escape(start), escape(end)),
+            })
+            .collect();
+        schema.insert("pattern".into(), format!("^[{classes}]*$").into());
+    }
+}
+
+/// How `Compare` relates the two fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn operator(self) -> &'static str {
+        match self {
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::GreaterOrEqual => ">=",
+            Self::Greater => ">",
+        }
+    }
+}
+
+/// Compares two fields of a struct, such as `start <= end`
+pub struct Compare<L, R> {
+    left: (&'static str, L),
+    comparison: Comparison,
+    right: (&'static str, R),
+}
+
+impl<L, R> Compare<L, R> {
+    pub fn new<T, FT>(
+        left: &'static str,
+        left_accessor: L,
+        comparison: Comparison,
+        right: &'static str,
+        right_accessor: R,
+    ) -> Self
+    where
+        L: FnOnce(&T) -> &FT,
+        R: FnOnce(&T) -> &FT,
+    {
+        Self {
+            left: (left, left_accessor),
+            comparison,
+            right: (right, right_accessor),
+        }
+    }
+}
+
+impl<T, C, FT, L, R> Validator<T, C> for Compare<L, R>
+where
+    FT: PartialOrd + Display,
+    L: FnOnce(&T) -> &FT,
+    R: FnOnce(&T) -> &FT,
+{
+    fn validate(self, value: &T, _ctx: &mut C) -> Result<(), String> {
+        let Compare {
+            left: (left_name, left),
+            comparison,
+            right: (right_name, right),
+        } = self;
+        let (left, right) = (left(value), right(value));
+        let holds = match comparison {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        };
+        match holds {
+            true => Ok(()),
+            false => Err(format!(
+                "expected {left_name} {} {right_name}, got {left} and {right}",
+                comparison.operator()
+            )),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::{fmt::Display, rc::Rc};
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+use super::{
+    content::{Content, ContentDeserializer},
+    error::Error,
+    FieldDecoders, FinalBuilder, Path, StructDeserializer, Validator,
+};
+
+type VersionFn<'a, T> =
+    Box<dyn FnOnce(ContentDeserializer) -> Result<T, Error<'static, ContentDeserializer>> + 'a>;
+
+/// Deserializes documents carrying a version key, each version having its own
+/// `StructDeserializer` and a migration to the next version.
+///
+/// Versions are registered from the oldest to the latest, every `migrate` converting the values
+/// of all the versions registered before it, so the result is always the latest shape. The input
+/// is buffered to find the version key, so it has to come from a self-describing format. The
+/// version key is removed before the document is passed to the version's deserializer.
+pub struct VersionedDeserializer<'a, T> {
+    version_key: String,
+    versions: Vec<(u64, VersionFn<'a, T>)>,
+    detector: Option<Box<dyn Fn(&[&str]) -> Option<u64> + 'a>>,
+}
+
+impl<'a, T: 'a> VersionedDeserializer<'a, T> {
+    pub fn new<N: ToString>(version_key: N) -> Self {
+        Self {
+            version_key: version_key.to_string(),
+            versions: Vec::new(),
+            detector: None,
+        }
+    }
+
+    pub fn version<FBARGS, FDS, FB, V, const FN: usize>(
+        mut self,
+        version: u64,
+        deserializer: StructDeserializer<T, FBARGS, FDS, FB, V, (), FN>,
+    ) -> Self
+    where
+        FBARGS: 'a,
+        FDS: FieldDecoders<FBARGS> + 'a,
+        FB: FinalBuilder<T, FBARGS> + 'a,
+        V: Validator<T> + 'a,
+    {
+        self.versions.push((
+            version,
+            Box::new(move |content| deserializer.deserialize(content)),
+        ));
+        self
+    }
+
+    /// Converts the values of all the versions registered so far into the next version
+    pub fn migrate<U: 'a, M: Fn(T) -> U + 'a>(self, migration: M) -> VersionedDeserializer<'a, U> {
+        let migration = Rc::new(migration);
+        let versions = self
+            .versions
+            .into_iter()
+            .map(|(version, deserialize)| {
+                let migration = Rc::clone(&migration);
+                let deserialize: VersionFn<'a, U> =
+                    Box::new(move |content| deserialize(content).map(|value| migration(value)));
+                (version, deserialize)
+            })
+            .collect();
+        VersionedDeserializer {
+            version_key: self.version_key,
+            versions,
+            detector: self.detector,
+        }
+    }
+
+    /// Picks the version from the keys of the document when it has no version key
+    pub fn detect_version<F: Fn(&[&str]) -> Option<u64> + 'a>(mut self, detector: F) -> Self {
+        self.detector = Some(Box::new(detector));
+        self
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(self, des: D) -> Result<T, Error<'de, D>> {
+        let human_readable = des.is_human_readable();
+        let Content::Map(mut entries) =
+            Content::deserialize(des).map_err(|e| Error::Deserialization(e, Path::default()))?
+        else {
+            return Err(custom("expected a map for a versioned struct"));
+        };
+        let version_position = entries
+            .iter()
+            .position(|(key, _)| key.as_str() == Some(self.version_key.as_str()));
+        let version = match version_position {
+            Some(position) => {
+                let (_, version) = entries.remove(position);
+                version.as_u64().ok_or_else(|| {
+                    custom(format_args!(
+                        "invalid value for `{}`, expected an unsigned integer",
+                        self.version_key
+                    ))
+                })?
+            }
+            None => {
+                let keys: Vec<&str> = entries.iter().filter_map(|(key, _)| key.as_str()).collect();
+                self.detector
+                    .as_ref()
+                    .and_then(|detector| detector(&keys))
+                    .ok_or_else(|| custom(format_args!("missing field `{}`", self.version_key)))?
+            }
+        };
+        let deserialize = self
+            .versions
+            .into_iter()
+            .find_map(|(v, deserialize)| (v == version).then_some(deserialize))
+            .ok_or_else(|| custom(format_args!("unknown version {version}")))?;
+        deserialize(ContentDeserializer::new(
+            Content::Map(entries),
+            human_readable,
+        ))
+        .map_err(|e| match e {
+            Error::Deserialization(e, path) => Error::Deserialization(D::Error::custom(e), path),
+            Error::Validation(e, path) => Error::Validation(e, path),
+        })
+    }
+}
+
+fn custom<'de, D: Deserializer<'de>>(msg: impl Display) -> Error<'de, D> {
+    Error::Deserialization(D::Error::custom(msg), Path::default())
+}
@@ -0,0 +1,37 @@
+//! Read-only descriptions of builders, for tooling that inspects them without running them
+
+/// Description of a `StructDeserializer` or `StructSerializer`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructDescription {
+    /// Name of the struct, as set with `name` or the type name without its path
+    pub name: &'static str,
+    pub type_name: &'static str,
+    /// Fields in the order they were added to the builder
+    pub fields: Vec<FieldDescription>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDescription {
+    /// Key of the field with `rename_all` applied, `None` for fields only matched by id
+    pub name: Option<String>,
+    /// Integer key the field is also matched against or emitted as
+    pub id: Option<u64>,
+    /// Type of the value read or written for the field
+    pub type_name: &'static str,
+    pub presence: Presence,
+    /// Every occurrence of the key is collected instead of being treated as a duplicate
+    pub repeatable: bool,
+    /// Description of the builder reading the field, for nested fields
+    pub nested: Option<StructDescription>,
+}
+
+/// Whether a field has to be present in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Presence {
+    Required,
+    Optional,
+    /// Optional field required depending on the other fields, with the condition
+    Conditional(String),
+    /// Not read from the input, the value is produced by the builder
+    Skipped,
+}
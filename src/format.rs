@@ -0,0 +1,48 @@
+//! Fields encoded differently in human-readable formats such as JSON and compact ones such as
+//! bincode
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{de::DeserializeWith, ser::SerializeWith};
+
+/// Codec for `field_with` using `H` when the format is human-readable and `C` otherwise, as told by
+/// `is_human_readable`
+pub struct ByFormat<H, C>(PhantomData<fn() -> (H, C)>);
+
+impl<FT: ?Sized, H: SerializeWith<FT>, C: SerializeWith<FT>> SerializeWith<FT> for ByFormat<H, C> {
+    fn serialize<S: Serializer>(value: &FT, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            H::serialize(value, serializer)
+        } else {
+            C::serialize(value, serializer)
+        }
+    }
+}
+
+impl<FT, H: DeserializeWith<FT>, C: DeserializeWith<FT>> DeserializeWith<FT> for ByFormat<H, C> {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FT, D::Error> {
+        if deserializer.is_human_readable() {
+            H::deserialize(deserializer)
+        } else {
+            C::deserialize(deserializer)
+        }
+    }
+}
+
+/// Codec using the field's own `Serialize` and `Deserialize` implementations, for the side of a
+/// `ByFormat` that needs no special handling
+pub struct Plain;
+
+impl<FT: Serialize + ?Sized> SerializeWith<FT> for Plain {
+    fn serialize<S: Serializer>(value: &FT, serializer: S) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+}
+
+impl<FT: for<'a> Deserialize<'a>> DeserializeWith<FT> for Plain {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FT, D::Error> {
+        FT::deserialize(deserializer)
+    }
+}
@@ -0,0 +1,38 @@
+use std::{collections::BTreeSet, sync::Mutex};
+
+static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+#[cfg(feature = "leaking")]
+static NAME_LISTS: Mutex<BTreeSet<&'static [&'static str]>> = Mutex::new(BTreeSet::new());
+
+/// Turns a name computed at runtime into a `&'static str` for serde APIs that require one.
+///
+/// Every distinct name is leaked only once, so repeatedly building the same serializer doesn't
+/// grow memory usage.
+pub(crate) fn intern(name: &str) -> &'static str {
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    match names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.to_owned().into_boxed_str());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+/// Same as `intern` for a list of names, such as the field names given to `deserialize_struct`
+#[cfg(feature = "leaking")]
+pub(crate) fn intern_names(
+    names: impl Iterator<Item = impl AsRef<str>>,
+) -> &'static [&'static str] {
+    let names: Vec<&'static str> = names.map(|name| intern(name.as_ref())).collect();
+    let mut lists = NAME_LISTS.lock().unwrap_or_else(|e| e.into_inner());
+    match lists.get(&names[..]) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static [&'static str] = names.leak();
+            lists.insert(interned);
+            interned
+        }
+    }
+}
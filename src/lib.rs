@@ -1,3 +1,39 @@
 #![allow(clippy::type_complexity)]
+pub mod built;
+pub mod case;
+pub mod codec;
 pub mod de;
+pub mod description;
+pub mod format;
+mod intern;
 pub mod ser;
+
+#[cfg(feature = "derive")]
+pub use serde_builder_derive::Builders;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
+
+/// Name of `T` without its module path and generic arguments, used as the struct name when none is
+/// given. Types that aren't a plain path, like tuples and references, keep their full name.
+pub(crate) fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let path = name.split('<').next().unwrap_or(name);
+    if !path
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+    {
+        return name;
+    }
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Compile-time check that a builder has at least one field, used by methods configuring the last
+/// added field
+pub(crate) struct NonEmpty<const FN: usize>;
+
+impl<const FN: usize> NonEmpty<FN> {
+    pub(crate) const ASSERT: () = assert!(FN > 0, "no field has been added yet");
+}
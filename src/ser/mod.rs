@@ -1,6 +1,18 @@
 use std::marker::PhantomData;
 
-use serde::{ser::SerializeStruct, Serialize, Serializer};
+use serde::{
+    ser::{SerializeMap, SerializeStruct},
+    Serialize, Serializer,
+};
+
+use concat_arrays::concat_arrays;
+
+use crate::{
+    case::Case,
+    description::{FieldDescription, Presence, StructDescription},
+    intern::intern,
+    short_type_name, NonEmpty,
+};
 
 pub trait FieldAccessor<T, FT> {
     fn get_field(self, parent: &T) -> &FT;
@@ -33,9 +45,116 @@ impl<T, FT, FA> Field<T, FT, FA> {
     }
 }
 
+/// Field of a `StructSerializer`, producing the value written for it
+pub trait SerializerField<T> {
+    type Value<'a>: Serialize
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn name(&self) -> &'static str;
+
+    /// Type of the value written for the field
+    fn type_name(&self) -> &'static str;
+
+    fn value(self, parent: &T) -> Self::Value<'_>;
+}
+
+impl<T, FT: Serialize, FA: FieldAccessor<T, FT>> SerializerField<T> for Field<T, FT, FA> {
+    type Value<'a>
+        = &'a FT
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<FT>()
+    }
+
+    fn value(self, parent: &T) -> &FT {
+        self.accessor.get_field(parent)
+    }
+}
+
+/// Serializes a value in place of its `Serialize` implementation, like the `serialize` function of
+/// a `#[serde(with = "...")]` module
+pub trait SerializeWith<FT: ?Sized> {
+    fn serialize<S: Serializer>(value: &FT, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Field serialized with `W` instead of its `Serialize` implementation
+pub struct FieldWith<T, FT, FA, W>(Field<T, FT, FA>, PhantomData<fn() -> W>);
+
+impl<T, FT, FA: FieldAccessor<T, FT>, W: SerializeWith<FT>> SerializerField<T>
+    for FieldWith<T, FT, FA, W>
+{
+    type Value<'a>
+        = SerializedWith<'a, FT, W>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<FT>()
+    }
+
+    fn value(self, parent: &T) -> SerializedWith<'_, FT, W> {
+        SerializedWith(self.0.accessor.get_field(parent), PhantomData)
+    }
+}
+
+pub struct SerializedWith<'a, FT, W>(&'a FT, PhantomData<fn() -> W>);
+
+impl<'a, FT, W: SerializeWith<FT>> Serialize for SerializedWith<'a, FT, W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        W::serialize(self.0, serializer)
+    }
+}
+
+/// Builder that serializes a `T`, implemented by the serializers of this crate
+pub trait TypeSerializer<T> {
+    fn serialize<S: Serializer>(self, value: &T, ser: S) -> Result<S::Ok, S::Error>;
+}
+
 pub struct StructSerializer<T, FIELDS = (), const FN: usize = 0> {
     target_phantom: PhantomData<T>,
     fields: FIELDS,
+    names: [FieldName; FN],
+    ids: [Option<u64>; FN],
+    options: Options,
+}
+
+/// Name of a field and the key written for it, computed when the field is added or `rename_all`
+/// is called rather than on every `serialize`
+struct FieldName {
+    name: &'static str,
+    /// Renamed keys are interned, as serde takes the field names of a struct as `&'static str`
+    key: &'static str,
+}
+
+impl FieldName {
+    fn new(name: &'static str, rename_all: Option<Case>) -> Self {
+        let key = match rename_all {
+            Some(case) => intern(&case.apply(name)),
+            None => name,
+        };
+        Self { name, key }
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    name: Option<&'static str>,
+    rename_all: Option<Case>,
+    emit_ids: bool,
 }
 
 impl<T> Default for StructSerializer<T> {
@@ -43,10 +162,61 @@ impl<T> Default for StructSerializer<T> {
         Self {
             target_phantom: PhantomData,
             fields: (),
+            names: [],
+            ids: [],
+            options: Options::default(),
         }
     }
 }
 
+impl<T, FIELDS, const FN: usize> StructSerializer<T, FIELDS, FN> {
+    /// Name of the struct given to the serializer, the type name without its path by default
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.options.name = Some(name);
+        self
+    }
+
+    /// Renames the keys of the fields added before and after it. Each distinct renamed key is
+    /// leaked once, as serde takes the field names of a struct as `&'static str`.
+    pub fn rename_all(mut self, case: Case) -> Self {
+        self.options.rename_all = Some(case);
+        for name in &mut self.names {
+            *name = FieldName::new(name.name, Some(case));
+        }
+        self
+    }
+
+    /// Assigns a numeric id to the last added field, emitted as its key when `emit_ids` is set
+    pub fn id(mut self, id: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = NonEmpty::<FN>::ASSERT;
+        self.ids[FN - 1] = Some(id);
+        self
+    }
+
+    /// Serializes the value as a map keyed by field ids instead of a struct, for compact formats
+    /// such as CBOR or MessagePack. Fields without an id keep using their name.
+    pub fn emit_ids(mut self) -> Self {
+        self.options.emit_ids = true;
+        self
+    }
+}
+
+fn describe_field<T, F: SerializerField<T>>(
+    field: &F,
+    name: &FieldName,
+    id: Option<u64>,
+) -> FieldDescription {
+    FieldDescription {
+        name: Some(name.key.to_owned()),
+        id,
+        type_name: field.type_name(),
+        presence: Presence::Required,
+        repeatable: false,
+        nested: None,
+    }
+}
+
 impl<T> StructSerializer<T> {
     pub fn new() -> Self {
         Self::default()
@@ -57,34 +227,71 @@ impl<T> StructSerializer<T> {
         name: &'static str,
         field_accessor: FA,
     ) -> StructSerializer<T, (Field<T, FT, FA>,), 1> {
+        self.push_field(name, Field::new(name, field_accessor))
+    }
+
+    /// Adds a field serialized with `W` instead of its `Serialize` implementation
+    pub fn field_with<W: SerializeWith<FT>, FT, FA: FnOnce(&T) -> &FT>(
+        self,
+        name: &'static str,
+        field_accessor: FA,
+    ) -> StructSerializer<T, (FieldWith<T, FT, FA, W>,), 1> {
+        self.push_field(
+            name,
+            FieldWith(Field::new(name, field_accessor), PhantomData),
+        )
+    }
+
+    fn push_field<F0>(self, name: &'static str, field: F0) -> StructSerializer<T, (F0,), 1> {
         let StructSerializer {
             target_phantom,
             fields: _,
+            names: _,
+            ids: _,
+            options,
         } = self;
         StructSerializer {
             target_phantom,
-            fields: (Field::new(name, field_accessor),),
+            fields: (field,),
+            names: [FieldName::new(name, options.rename_all)],
+            ids: [None],
+            options,
         }
     }
 }
 
 macro_rules! add_field_impl {
-    ($($len:expr => ($($n:tt $fname:ident $faname:ident),+) $fname2:ident $faname2:ident)+) => {
+    ($($len:expr => ($($n:tt $fname:ident),+) $fname2:ident)+) => {
         $(
-            impl<T, $($fname, $faname),+> StructSerializer<T, ($(Field<T, $fname, $faname>,)+), $len>
-            where
-                $($faname: FieldAccessor<T, $fname>,)+
-            {
-                pub fn field<$fname2, $faname2: FnOnce(&T) -> &$fname2>(self, name: &'static str, field_accessor: $faname2) ->
-                    StructSerializer<T, ($(Field<T, $fname, $faname>,)+ Field<T, $fname2, $faname2>,), {$len + 1}>
+            impl<T, $($fname),+> StructSerializer<T, ($($fname,)+), $len> {
+                pub fn field<FT, FA: FnOnce(&T) -> &FT>(self, name: &'static str, field_accessor: FA) ->
+                    StructSerializer<T, ($($fname,)+ Field<T, FT, FA>,), {$len + 1}>
                 {
+                    self.push_field(name, Field::new(name, field_accessor))
+                }
+
+                /// Adds a field serialized with `W` instead of its `Serialize` implementation
+                pub fn field_with<W: SerializeWith<FT>, FT, FA: FnOnce(&T) -> &FT>(self, name: &'static str, field_accessor: FA) ->
+                    StructSerializer<T, ($($fname,)+ FieldWith<T, FT, FA, W>,), {$len + 1}>
+                {
+                    self.push_field(name, FieldWith(Field::new(name, field_accessor), PhantomData))
+                }
+
+                fn push_field<$fname2>(self, name: &'static str, field: $fname2) -> StructSerializer<T, ($($fname,)+ $fname2,), {$len + 1}> {
                     let StructSerializer {
                         target_phantom,
                         fields,
+                        names,
+                        ids,
+                        options,
                     } = self;
+                    #[allow(clippy::drop_non_drop)]
                     StructSerializer {
                         target_phantom,
-                        fields: ($(fields.$n,)+ Field::new(name, field_accessor)),
+                        fields: ($(fields.$n,)+ field),
+                        names: concat_arrays!(names, [FieldName::new(name, options.rename_all)]),
+                        ids: concat_arrays!(ids, [None]),
+                        options,
                     }
                 }
             }
@@ -93,61 +300,93 @@ macro_rules! add_field_impl {
 }
 
 add_field_impl! {
-    1 => (0 F0 F0A) F1 F1A
-    2 => (0 F0 F0A, 1 F1 F1A) F2 F2A
-    3 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A) F3 F3A
-    4 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A) F4 F4A
-    5 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A) F5 F5A
-    6 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A) F6 F6A
-    7 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A) F7 F7A
-    8 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A) F8 F8A
-    9 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A) F9 F9A
-    10 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A, 9 F9 F9A) F10 F10A
-    11 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A, 9 F9 F9A, 10 F10 F10A) F11 F11A
-    12 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A, 9 F9 F9A, 10 F10 F10A, 11 F11 F11A) F12 F12A
-    13 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A, 9 F9 F9A, 10 F10 F10A, 11 F11 F11A, 12 F12 F12A) F13 F13A
-    14 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A, 9 F9 F9A, 10 F10 F10A, 11 F11 F11A, 12 F12 F12A, 13 F13 F13A) F14 F14A
-    15 => (0 F0 F0A, 1 F1 F1A, 2 F2 F2A, 3 F3 F3A, 4 F4 F4A, 5 F5 F5A, 6 F6 F6A, 7 F7 F7A, 8 F8 F8A, 9 F9 F9A, 10 F10 F10A, 11 F11 F11A, 12 F12 F12A, 13 F13 F13A, 14 F14 F14A) F15 F15A
+    1 => (0 F0) F1
+    2 => (0 F0, 1 F1) F2
+    3 => (0 F0, 1 F1, 2 F2) F3
+    4 => (0 F0, 1 F1, 2 F2, 3 F3) F4
+    5 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4) F5
+    6 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5) F6
+    7 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6) F7
+    8 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7) F8
+    9 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8) F9
+    10 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8, 9 F9) F10
+    11 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8, 9 F9, 10 F10) F11
+    12 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8, 9 F9, 10 F10, 11 F11) F12
+    13 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8, 9 F9, 10 F10, 11 F11, 12 F12) F13
+    14 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8, 9 F9, 10 F10, 11 F11, 12 F12, 13 F13) F14
+    15 => (0 F0, 1 F1, 2 F2, 3 F3, 4 F4, 5 F5, 6 F6, 7 F7, 8 F8, 9 F9, 10 F10, 11 F11, 12 F12, 13 F13, 14 F14) F15
 }
 
 macro_rules! ser_impl {
-    ($($len:expr => $($ftname:ident $faname:ident $fvname:ident),+)+) => {
+    ($($len:expr => $($fname:ident $fvname:ident),+)+) => {
         $(
-            impl<T, $($ftname, $faname),+> StructSerializer<T, ($(Field<T, $ftname, $faname>,)+), $len>
-            where
-                $(
-                $ftname: Serialize,
-                $faname: FieldAccessor<T, $ftname>,
-                )+
-            {
+            impl<T, $($fname: SerializerField<T>),+> StructSerializer<T, ($($fname,)+), $len> {
                 pub fn serialize<S: Serializer>(self, value: &T, ser: S) -> Result<S::Ok, S::Error> {
-                    let mut struct_ser_state = ser.serialize_struct(std::any::type_name::<T>(), $len)?;
-                    let ($($fvname,)+) = self.fields;
+                    let StructSerializer {
+                        target_phantom: _,
+                        fields: ($($fvname,)+),
+                        names,
+                        ids,
+                        options: Options { name, rename_all: _, emit_ids },
+                    } = self;
+                    let mut names = names.iter();
+                    if emit_ids {
+                        let mut map_ser_state = ser.serialize_map(Some($len))?;
+                        let mut ids = ids.into_iter();
+                        $(
+                            let name = names.next().expect("a name for every field");
+                            match ids.next().flatten() {
+                                Some(id) => map_ser_state.serialize_entry(&id, &$fvname.value(value))?,
+                                None => map_ser_state.serialize_entry(name.key, &$fvname.value(value))?,
+                            }
+                        )+
+                        return map_ser_state.end();
+                    }
+                    let mut struct_ser_state = ser.serialize_struct(name.unwrap_or_else(short_type_name::<T>), $len)?;
                     $(
-                        struct_ser_state.serialize_field($fvname.name, $fvname.accessor.get_field(value))?;
+                        let name = names.next().expect("a name for every field");
+                        struct_ser_state.serialize_field(name.key, &$fvname.value(value))?;
                     )+
                     struct_ser_state.end()
                 }
+
+                /// Description of the fields the builder writes, in the order they were added
+                pub fn describe(&self) -> StructDescription {
+                    let ($($fvname,)+) = &self.fields;
+                    let mut names = self.names.iter();
+                    let mut ids = self.ids.into_iter();
+                    StructDescription {
+                        name: self.options.name.unwrap_or_else(short_type_name::<T>),
+                        type_name: std::any::type_name::<T>(),
+                        fields: vec![$(describe_field($fvname, names.next().expect("a name for every field"), ids.next().flatten()),)+],
+                    }
+                }
+            }
+
+            impl<T, $($fname: SerializerField<T>),+> TypeSerializer<T> for StructSerializer<T, ($($fname,)+), $len> {
+                fn serialize<S: Serializer>(self, value: &T, ser: S) -> Result<S::Ok, S::Error> {
+                    self.serialize(value, ser)
+                }
             }
         )+
     }
 }
 
 ser_impl! {
-    1 => F0 F0A f0
-    2 => F0 F0A f0, F1 F1A f1
-    3 => F0 F0A f0, F1 F1A f1, F2 F2A f2
-    4 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3
-    5 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4
-    6 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5
-    7 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6
-    8 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7
-    9 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8
-    10 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9
-    11 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9, F10 F10A f10
-    12 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9, F10 F10A f10, F11 F11A f11
-    13 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9, F10 F10A f10, F11 F11A f11, F12 F12A f12
-    14 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9, F10 F10A f10, F11 F11A f11, F12 F12A f12, F13 F13A f13
-    15 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9, F10 F10A f10, F11 F11A f11, F12 F12A f12, F13 F13A f13, F14 F14A f14
-    16 => F0 F0A f0, F1 F1A f1, F2 F2A f2, F3 F3A f3, F4 F4A f4, F5 F5A f5, F6 F6A f6, F7 F7A f7, F8 F8A f8, F9 F9A f9, F10 F10A f10, F11 F11A f11, F12 F12A f12, F13 F13A f13, F14 F14A f14, F15 F15A f15
+    1 => F0 f0
+    2 => F0 f0, F1 f1
+    3 => F0 f0, F1 f1, F2 f2
+    4 => F0 f0, F1 f1, F2 f2, F3 f3
+    5 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4
+    6 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5
+    7 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6
+    8 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7
+    9 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8
+    10 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9
+    11 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9, F10 f10
+    12 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9, F10 f10, F11 f11
+    13 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9, F10 f10, F11 f11, F12 f12
+    14 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9, F10 f10, F11 f11, F12 f12, F13 f13
+    15 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9, F10 f10, F11 f11, F12 f12, F13 f13, F14 f14
+    16 => F0 f0, F1 f1, F2 f2, F3 f3, F4 f4, F5 f5, F6 f6, F7 f7, F8 f8, F9 f9, F10 f10, F11 f11, F12 f12, F13 f13, F14 f14, F15 f15
 }
@@ -0,0 +1,33 @@
+use serde_builder::{
+    codec::StructCodec,
+    de::{KeyMatching, StructDeserializer},
+    ser::StructSerializer,
+};
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn builders_are_send_and_sync() {
+    let deserializer = StructDeserializer::new()
+        .field("x")
+        .field("y")
+        .key_matching(KeyMatching::custom(|key| key.to_lowercase()))
+        .final_builder(|x, y| Point { x, y });
+    assert_send_sync(&deserializer);
+
+    let serializer = StructSerializer::new()
+        .field("x", |p: &Point| &p.x)
+        .field("y", |p: &Point| &p.y);
+    assert_send_sync(&serializer);
+
+    let codec = StructCodec::new()
+        .field("x", |p: &Point| &p.x)
+        .field("y", |p: &Point| &p.y)
+        .final_builder(|x, y| Point { x, y });
+    assert_send_sync(&codec);
+}
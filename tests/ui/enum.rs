@@ -0,0 +1,9 @@
+use serde_builder::Builders;
+
+#[derive(Builders)]
+enum Shape {
+    Circle { radius: f64 },
+    Square { side: f64 },
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use serde_builder::Builders;
+
+#[derive(Builders)]
+struct Point(i32, i32);
+
+fn main() {}